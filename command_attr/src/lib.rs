@@ -64,6 +64,7 @@ macro_rules! match_options {
 /// | `#[delimiters(delims)]`                                                        | Argument delimiters specific to this command. Overrides the global list of delimiters in the framework.  | `delims` is a comma separated list of strings                                                                                                                                                                                     |
 /// | `#[min_args(min)]` <br /> `#[max_args(max)]` <br /> `#[num_args(min_and_max)]` | The expected length of arguments that the command must receive in order to function correctly.           | `min`, `max` and `min_and_max` are 16-bit, unsigned integers.                                                                                                                                                                     |
 /// | `#[required_permissions(perms)]`                                               | Set of permissions the user must possess. <br /> In order for this attribute to work, "Presence Intent" and "Server Member Intent" options in bot application must be enabled and all intent flags must be enabled during client creation. | `perms` is a comma separated list of permission names.<br /> These can be found at [Discord's official documentation](https://discord.com/developers/docs/topics/permissions).        |
+/// | `#[bot_required_permissions(perms)]`                                           | Set of permissions the bot must possess to perform the command.                                          | `perms` is a comma separated list of permission names.<br /> These can be found at [Discord's official documentation](https://discord.com/developers/docs/topics/permissions).        |
 /// | `#[allowed_roles(roles)]`                                                      | Set of roles the user must possess.                                                                      | `roles` is a comma separated list of role names.                                                                                                                                                                                  |
 /// | `#[help_available]` <br /> `#[help_available(b)]`                              | If the command should be displayed in the help message.                                                  | `b` is a boolean. If no boolean is provided, the value is assumed to be `true`.                                                                                                                                                   |
 /// | `#[only_in(ctx)]`                                                              | Which environment the command can be executed in.                                                        | `ctx` is a string with the accepted values `guild`/`guilds` and `dm`/`dms` (Direct Message).                                                                                                                                      |
@@ -139,6 +140,7 @@ pub fn command(attr: TokenStream, input: TokenStream) -> TokenStream {
                     min_args;
                     max_args;
                     required_permissions;
+                    bot_required_permissions;
                     allowed_roles;
                     help_available;
                     only_in;
@@ -162,6 +164,7 @@ pub fn command(attr: TokenStream, input: TokenStream) -> TokenStream {
         max_args,
         allowed_roles,
         required_permissions,
+        bot_required_permissions,
         help_available,
         only_in,
         owners_only,
@@ -206,6 +209,7 @@ pub fn command(attr: TokenStream, input: TokenStream) -> TokenStream {
             max_args: #max_args,
             allowed_roles: &[#(#allowed_roles),*],
             required_permissions: #required_permissions,
+            bot_required_permissions: #bot_required_permissions,
             help_available: #help_available,
             only_in: #only_in,
             owners_only: #owners_only,
@@ -592,6 +596,7 @@ pub fn help(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// | `#[help_available]` <br /> `#[help_available(b)]`     | If the group should be displayed in the help message.                            | `b` is a boolean. If no boolean is provided, the value is assumed to be `true`.                             |
 /// | `#[checks(identifiers)]`                              | Preconditions that must met before the command's execution.                      | `identifiers` is a comma separated list of identifiers referencing functions marked by the `#[check]` macro |
 /// | `#[required_permissions(perms)]`                      | Set of permissions the user must possess. <br /> In order for this attribute to work, "Presence Intent" and "Server Member Intent" options in bot application must be enabled and all intent flags must be enabled during client creation. | `perms` is a comma separated list of permission names.<br /> These can be found at [Discord's official documentation](https://discord.com/developers/docs/topics/permissions). |
+/// | `#[bot_required_permissions(perms)]`                  | Set of permissions the bot must possess to perform the group's commands.         | `perms` is a comma separated list of permission names.<br /> These can be found at [Discord's official documentation](https://discord.com/developers/docs/topics/permissions). |
 /// | `#[default_command(cmd)]`                             | A command to execute if none of the group's prefixes are given.                  | `cmd` is an identifier referencing a function marked by the `#[command]` macro                              |
 /// | `#[description(desc)]` <br /> `#[description = desc]` | The group's description or summary.                                              | `desc` is a string describing the group.                                                                    |
 /// | `#[summary(desc)]` <br /> `#[summary = desc]`         | A summary group description displayed when shown multiple groups.                | `desc` is a string summaryly describing the group.                                                          |
@@ -660,6 +665,7 @@ pub fn group(attr: TokenStream, input: TokenStream) -> TokenStream {
                 help_available;
                 allowed_roles;
                 required_permissions;
+                bot_required_permissions;
                 checks;
                 default_command;
                 commands;
@@ -676,6 +682,7 @@ pub fn group(attr: TokenStream, input: TokenStream) -> TokenStream {
         help_available,
         allowed_roles,
         required_permissions,
+        bot_required_permissions,
         checks,
         default_command,
         description,
@@ -714,6 +721,7 @@ pub fn group(attr: TokenStream, input: TokenStream) -> TokenStream {
             help_available: #help_available,
             allowed_roles: &[#(#allowed_roles),*],
             required_permissions: #required_permissions,
+            bot_required_permissions: #bot_required_permissions,
             checks: #checks,
             default_command: #default_command,
             description: #description,
@@ -446,6 +446,7 @@ pub struct Options {
     pub max_args: AsOption<u16>,
     pub allowed_roles: Vec<String>,
     pub required_permissions: Permissions,
+    pub bot_required_permissions: Permissions,
     pub help_available: bool,
     pub only_in: OnlyIn,
     pub owners_only: bool,
@@ -619,6 +620,7 @@ pub struct GroupOptions {
     pub help_available: bool,
     pub allowed_roles: Vec<String>,
     pub required_permissions: Permissions,
+    pub bot_required_permissions: Permissions,
     pub checks: Checks,
     pub default_command: AsOption<Ident>,
     pub description: AsOption<String>,
@@ -71,6 +71,21 @@ impl CreateAllowedMentions {
         Self::default()
     }
 
+    /// Allows no mentions at all, including of the author of a replied to message.
+    pub fn none() -> Self {
+        Self::new().replied_user(false)
+    }
+
+    /// Allows mentions of only the given users, without any roles or @everyone.
+    pub fn only_users(users: impl IntoIterator<Item = impl Into<UserId>>) -> Self {
+        Self::new().users(users)
+    }
+
+    /// Allows mentions of only the given roles, without any users or @everyone.
+    pub fn only_roles(roles: impl IntoIterator<Item = impl Into<RoleId>>) -> Self {
+        Self::new().roles(roles)
+    }
+
     /// Toggles mentions for all users. Overrides [`Self::users`] if it was previously set.
     pub fn all_users(mut self, allow: bool) -> Self {
         if allow {
@@ -136,3 +151,27 @@ impl CreateAllowedMentions {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::{json, to_value};
+
+    #[test]
+    fn none() {
+        let json = to_value(CreateAllowedMentions::none()).unwrap();
+        assert_eq!(json, json!({"parse": [], "users": [], "roles": [], "replied_user": false}));
+    }
+
+    #[test]
+    fn only_users() {
+        let json = to_value(CreateAllowedMentions::only_users([UserId::new(1), UserId::new(2)]));
+        assert_eq!(json.unwrap(), json!({"parse": [], "users": ["1", "2"], "roles": []}));
+    }
+
+    #[test]
+    fn only_roles() {
+        let json = to_value(CreateAllowedMentions::only_roles([RoleId::new(3)])).unwrap();
+        assert_eq!(json, json!({"parse": [], "users": [], "roles": ["3"]}));
+    }
+}
@@ -1,16 +1,24 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+#[cfg(feature = "http")]
+use futures::stream::{self, Stream};
+#[cfg(feature = "http")]
+use reqwest::header::CONTENT_TYPE;
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 #[cfg(feature = "http")]
 use url::Url;
 
+#[cfg(feature = "http")]
+use crate::constants;
 #[cfg(feature = "http")]
 use crate::error::Error;
 use crate::error::Result;
 #[cfg(feature = "http")]
 use crate::http::Http;
 use crate::model::id::AttachmentId;
+#[cfg(feature = "http")]
+use crate::model::ModelError;
 
 /// [Discord docs] with the caveat at the top "For the attachments array in Message Create/Edit
 /// requests, only the id is required."
@@ -23,6 +31,43 @@ pub(crate) struct ExistingAttachment {
     // ephemeral (ephemeral in particular seems pretty interesting)
 }
 
+/// An entry in the attachment list of an edited message, referring to either an attachment that
+/// already exists on the message or to a newly uploaded one.
+///
+/// Discord displays attachments in the order they are listed, so this can be used to interleave
+/// existing attachments with new uploads.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum NewOrExisting {
+    /// An attachment already present on the message, by Id.
+    Existing(AttachmentId),
+    /// A newly uploaded attachment, by its index in the list of new attachments.
+    New(usize),
+}
+
+impl serde::Serialize for NewOrExisting {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Entry<T> {
+            id: T,
+        }
+
+        match self {
+            Self::Existing(id) => Entry {
+                id,
+            }
+            .serialize(serializer),
+            Self::New(index) => Entry {
+                id: index,
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
 /// Enum that allows a user to pass a [`Path`] or a [`File`] type to [`send_files`]
 ///
 /// [`send_files`]: crate::model::id::ChannelId::send_files
@@ -32,6 +77,47 @@ pub(crate) struct ExistingAttachment {
 pub struct CreateAttachment {
     pub data: Vec<u8>,
     pub filename: String,
+    pub description: Option<String>,
+    pub content_type: Option<String>,
+    pub(crate) streamed: Option<StreamedFile>,
+}
+
+/// A local file that is only read once the request body is sent, instead of being held in memory.
+#[derive(Clone, Debug)]
+pub(crate) struct StreamedFile {
+    pub path: PathBuf,
+    pub len: u64,
+}
+
+#[cfg(feature = "http")]
+impl StreamedFile {
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    /// Reads the file in chunks, opening it on first poll.
+    pub(crate) fn stream(
+        &self,
+    ) -> impl Stream<Item = std::io::Result<Vec<u8>>> + Send + Sync + 'static {
+        let path = self.path.clone();
+
+        stream::try_unfold(None, move |file: Option<File>| {
+            let path = path.clone();
+            async move {
+                let mut file = match file {
+                    Some(file) => file,
+                    None => File::open(path).await?,
+                };
+
+                let mut chunk = vec![0; Self::CHUNK_SIZE];
+                let read = file.read(&mut chunk).await?;
+                if read == 0 {
+                    return Ok(None);
+                }
+
+                chunk.truncate(read);
+                Ok(Some((chunk, Some(file))))
+            }
+        })
+    }
 }
 
 impl CreateAttachment {
@@ -40,6 +126,9 @@ impl CreateAttachment {
         CreateAttachment {
             data: data.into(),
             filename: filename.into(),
+            description: None,
+            content_type: None,
+            streamed: None,
         }
     }
 
@@ -63,6 +152,44 @@ impl CreateAttachment {
         Ok(CreateAttachment {
             data,
             filename: filename.to_string_lossy().to_string(),
+            description: None,
+            content_type: None,
+            streamed: None,
+        })
+    }
+
+    /// Builds an [`CreateAttachment`] from a local file without reading it into memory.
+    ///
+    /// The file is read in chunks while the request is being sent, which is preferable for large
+    /// uploads. The file must still exist and be unchanged by the time the request is made.
+    ///
+    /// **Note**: As the data is not loaded, [`Self::data`] is empty, so this can't be used where
+    /// the data is sent as base64 with [`Self::to_base64`], such as for avatars or icons. Use
+    /// [`Self::path`] for those instead.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::Io`] if the file metadata can't be read.
+    pub async fn path_streaming(path: impl AsRef<Path>) -> Result<CreateAttachment> {
+        let path = path.as_ref();
+        let metadata = tokio::fs::metadata(path).await?;
+
+        let filename = path.file_name().filter(|_| metadata.is_file()).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "attachment path must not be a directory",
+            )
+        })?;
+
+        Ok(CreateAttachment {
+            data: Vec::new(),
+            filename: filename.to_string_lossy().to_string(),
+            description: None,
+            content_type: None,
+            streamed: Some(StreamedFile {
+                path: path.to_path_buf(),
+                len: metadata.len(),
+            }),
         })
     }
 
@@ -78,36 +205,138 @@ impl CreateAttachment {
         Ok(CreateAttachment {
             data,
             filename: filename.into(),
+            description: None,
+            content_type: None,
+            streamed: None,
         })
     }
 
-    /// Builds an [`CreateAttachment`] by downloading attachment data from a URL.
+    /// Builds an [`CreateAttachment`] by downloading attachment data from a URL, using the
+    /// client of the given [`Http`].
+    ///
+    /// The filename is taken from the last segment of the URL's path, and the content type from
+    /// the response, if it has one.
     ///
     /// # Errors
     ///
-    /// [`Error::Url`] if the URL is invalid, [`Error::Http`] if downloading the data fails.
+    /// [`Error::Url`] if the URL is invalid, [`Error::Http`] if downloading the data fails or the
+    /// server responds with an error status. Returns a [`ModelError::AttachmentTooLarge`] if the
+    /// file is larger than [`constants::ATTACHMENT_DOWNLOAD_LIMIT`].
+    ///
+    /// [`ModelError::AttachmentTooLarge`]: crate::model::ModelError::AttachmentTooLarge
     #[cfg(feature = "http")]
     pub async fn url(http: impl AsRef<Http>, url: &str) -> Result<CreateAttachment> {
-        let url = Url::parse(url).map_err(|_| Error::Url(url.to_string()))?;
+        Self::download(http.as_ref(), url, constants::ATTACHMENT_DOWNLOAD_LIMIT).await
+    }
 
-        let response = http.as_ref().client.get(url.clone()).send().await?;
-        let data = response.bytes().await?.to_vec();
+    #[cfg(feature = "http")]
+    async fn download(http: &Http, url: &str, limit: u64) -> Result<CreateAttachment> {
+        let url = Url::parse(url).map_err(|_| Error::Url(url.to_string()))?;
 
         let filename = url
             .path_segments()
             .and_then(Iterator::last)
-            .ok_or_else(|| Error::Url(url.to_string()))?;
+            .filter(|filename| !filename.is_empty())
+            .ok_or_else(|| Error::Url(url.to_string()))?
+            .to_string();
+
+        let mut response = http.client.get(url).send().await?.error_for_status()?;
+        let too_large = |total| {
+            Error::Model(ModelError::AttachmentTooLarge {
+                total,
+                limit,
+            })
+        };
+
+        // The advertised length is checked up front, but the body is still counted as it is
+        // read, as it can be missing or wrong.
+        if let Some(total) = response.content_length().filter(|total| *total > limit) {
+            return Err(too_large(total));
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(ToString::to_string);
+
+        let mut data = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            data.extend_from_slice(&chunk);
+            if data.len() as u64 > limit {
+                return Err(too_large(data.len() as u64));
+            }
+        }
 
         Ok(CreateAttachment {
             data,
-            filename: filename.to_string(),
+            filename,
+            description: None,
+            content_type,
+            streamed: None,
         })
     }
 
+    /// Sets a description for the attachment, used as alt text by clients.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the MIME type of the attachment, such as `text/plain` for a `.log` file.
+    ///
+    /// By default, the type is inferred from the extension of [`Self::filename`].
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Returns the size of the attachment in bytes, including that of a streamed file which is not
+    /// held in memory.
+    #[cfg(feature = "http")]
+    pub(crate) fn size(&self) -> u64 {
+        match &self.streamed {
+            Some(streamed) => streamed.len,
+            None => self.data.len() as u64,
+        }
+    }
+
+    /// Returns a hash of the attachment's data, or [`None`] for a file built with
+    /// [`Self::path_streaming`], whose data is not held in memory.
+    ///
+    /// The hash only depends on the bytes, not on the filename or any other field, and is stable
+    /// across builds and platforms. Comparing hashes can be used to skip uploading a file that
+    /// is identical to one which was uploaded before, and to keep the existing attachment
+    /// instead, such as with [`EditWebhookMessage::keep_existing_attachment`].
+    ///
+    /// **Note**: This is not a cryptographic hash, so it must not be relied on for data supplied
+    /// by untrusted users.
+    ///
+    /// [`EditWebhookMessage::keep_existing_attachment`]: super::EditWebhookMessage::keep_existing_attachment
+    #[must_use]
+    pub fn content_hash(&self) -> Option<u64> {
+        // 64-bit FNV-1a, which unlike the standard library's hashers has a specified output.
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0100_0000_01b3;
+
+        if self.streamed.is_some() {
+            return None;
+        }
+
+        let hash = self
+            .data
+            .iter()
+            .fold(OFFSET_BASIS, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME));
+        Some(hash)
+    }
+
     /// Converts the stored data to the base64 representation.
     ///
     /// This is used in the library internally because Discord expects image data as base64 in many
     /// places.
+    ///
+    /// **Note**: Files built with [`Self::path_streaming`] are not supported, as their data is not
+    /// held in memory. Only the empty [`Self::data`] would be encoded.
     #[must_use]
     pub fn to_base64(&self) -> String {
         let mut encoded = {
@@ -118,3 +347,93 @@ impl CreateAttachment {
         encoded
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "http")]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn path_streaming() {
+        let path = std::env::temp_dir().join("serenity_path_streaming_test.bin");
+        let data = (0..200_000u32).map(|i| i as u8).collect::<Vec<_>>();
+        tokio::fs::write(&path, &data).await.unwrap();
+
+        let attachment = CreateAttachment::path_streaming(&path).await.unwrap();
+        assert_eq!(attachment.filename, "serenity_path_streaming_test.bin");
+        assert!(attachment.data.is_empty());
+
+        let streamed = attachment.streamed.unwrap();
+        assert_eq!(streamed.len, data.len() as u64);
+
+        let chunks: Vec<Vec<u8>> = streamed.stream().try_collect().await.unwrap();
+        assert_eq!(chunks.concat(), data);
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn content_hash() {
+        let image = CreateAttachment::bytes(b"image data".to_vec(), "a.png");
+        let renamed = CreateAttachment::bytes(b"image data".to_vec(), "b.png").description("b");
+        let other = CreateAttachment::bytes(b"other data".to_vec(), "a.png");
+
+        assert_eq!(image.content_hash(), renamed.content_hash());
+        assert_ne!(image.content_hash(), other.content_hash());
+        let single = CreateAttachment::bytes(b"a".to_vec(), "a.txt");
+        assert_eq!(single.content_hash(), Some(0xaf63_dc4c_8601_ec8c));
+
+        let path = std::env::temp_dir().join("serenity_content_hash_test.bin");
+        tokio::fs::write(&path, b"image data").await.unwrap();
+        let read = CreateAttachment::path(&path).await.unwrap();
+        assert_eq!(read.content_hash(), image.content_hash());
+        let streamed = CreateAttachment::path_streaming(&path).await.unwrap();
+        assert_eq!(streamed.content_hash(), None);
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    /// Serves a single HTTP response on a local port, returning the URL of `path` on it.
+    fn serve_once(path: &str, response: &'static [u8]) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            assert!(socket.read(&mut request).unwrap() > 0);
+            socket.write_all(response).unwrap();
+        });
+
+        format!("http://{addr}{path}")
+    }
+
+    const PNG_RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\n\
+        Content-Length: 8\r\nConnection: close\r\n\r\n\x89PNG\r\n\x1a\n";
+
+    #[tokio::test]
+    async fn url() {
+        let http = Http::new("token");
+
+        let url = serve_once("/images/logo.png?size=64", PNG_RESPONSE);
+        let attachment = CreateAttachment::url(&http, &url).await.unwrap();
+        assert_eq!(attachment.filename, "logo.png");
+        assert_eq!(attachment.data, b"\x89PNG\r\n\x1a\n");
+        assert_eq!(attachment.content_type.as_deref(), Some("image/png"));
+
+        let url = serve_once("/images/logo.png", PNG_RESPONSE);
+        let err = CreateAttachment::download(&http, &url, 4).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Model(ModelError::AttachmentTooLarge {
+                total: 8,
+                limit: 4
+            })
+        ));
+
+        let err = CreateAttachment::url(&http, "http://127.0.0.1/").await.unwrap_err();
+        assert!(matches!(err, Error::Url(_)));
+    }
+}
@@ -30,6 +30,49 @@ impl serde::Serialize for CreateActionRow {
     }
 }
 
+impl CreateActionRow {
+    /// Disables every button and select menu in the row. Input texts can't be disabled and are left
+    /// as they are.
+    pub fn disable_all(self) -> Self {
+        match self {
+            Self::Buttons(buttons) => {
+                Self::Buttons(buttons.into_iter().map(|button| button.disabled(true)).collect())
+            },
+            Self::SelectMenu(select_menu) => Self::SelectMenu(select_menu.disabled(true)),
+            Self::InputText(input_text) => Self::InputText(input_text),
+        }
+    }
+}
+
+impl From<ActionRow> for CreateActionRow {
+    /// Rebuilds an action row of an existing message, such as to edit it while keeping most of
+    /// its components.
+    ///
+    /// A row is assumed to hold either buttons, a single select menu or a single input text, as
+    /// Discord requires.
+    fn from(row: ActionRow) -> Self {
+        let mut components = row.components.into_iter();
+        match components.next() {
+            Some(ActionRowComponent::SelectMenu(select_menu)) => {
+                Self::SelectMenu(select_menu.into())
+            },
+            Some(ActionRowComponent::InputText(input_text)) => {
+                Self::InputText(CreateInputText(input_text))
+            },
+            first => Self::Buttons(
+                first
+                    .into_iter()
+                    .chain(components)
+                    .filter_map(|component| match component {
+                        ActionRowComponent::Button(button) => Some(CreateButton(button)),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
 /// A builder for creating a button component in a message
 #[derive(Clone, Debug, Serialize)]
 #[must_use]
@@ -224,6 +267,32 @@ impl CreateSelectMenu {
     }
 }
 
+impl From<SelectMenu> for CreateSelectMenu {
+    fn from(select_menu: SelectMenu) -> Self {
+        let kind = match select_menu.kind {
+            ComponentType::UserSelect => CreateSelectMenuKind::User,
+            ComponentType::RoleSelect => CreateSelectMenuKind::Role,
+            ComponentType::MentionableSelect => CreateSelectMenuKind::Mentionable,
+            ComponentType::ChannelSelect => CreateSelectMenuKind::Channel {
+                channel_types: Some(select_menu.channel_types)
+                    .filter(|channel_types| !channel_types.is_empty()),
+            },
+            _ => CreateSelectMenuKind::String {
+                options: select_menu.options.into_iter().map(Into::into).collect(),
+            },
+        };
+
+        Self {
+            custom_id: select_menu.custom_id.unwrap_or_default(),
+            placeholder: select_menu.placeholder,
+            min_values: select_menu.min_values,
+            max_values: select_menu.max_values,
+            disabled: Some(select_menu.disabled),
+            kind,
+        }
+    }
+}
+
 /// A builder for creating an option of a select menu component in a message
 ///
 /// [Discord docs](https://discord.com/developers/docs/interactions/message-components#select-menu-object-select-option-structure)
@@ -284,6 +353,18 @@ impl CreateSelectMenuOption {
     }
 }
 
+impl From<SelectMenuOption> for CreateSelectMenuOption {
+    fn from(option: SelectMenuOption) -> Self {
+        Self {
+            label: option.label,
+            value: option.value,
+            description: option.description,
+            emoji: option.emoji,
+            default: Some(option.default),
+        }
+    }
+}
+
 /// A builder for creating an input text component in a modal
 ///
 /// [Discord docs](https://discord.com/developers/docs/interactions/message-components#text-inputs-text-input-structure).
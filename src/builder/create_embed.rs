@@ -54,6 +54,53 @@ impl CreateEmbed {
         self
     }
 
+    /// Set the colour of the left-hand side of the embed from its hexadecimal representation.
+    ///
+    /// This is an alias of [`Self::colour_hex`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModelError::InvalidColour`] if the string is malformed.
+    #[inline]
+    pub fn color_hex(self, hex: &str) -> crate::Result<Self> {
+        self.colour_hex(hex)
+    }
+
+    /// Set the colour of the left-hand side of the embed from its hexadecimal representation, such
+    /// as `#3498DB`. The `#` is optional.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModelError::InvalidColour`] if the string is malformed.
+    pub fn colour_hex(self, hex: &str) -> crate::Result<Self> {
+        match Colour::from_hex(hex) {
+            Some(colour) => Ok(self.colour(colour)),
+            None => Err(crate::Error::Model(ModelError::InvalidColour(hex.to_string()))),
+        }
+    }
+
+    /// Set the colour of the embed to the green of [`colours::css::POSITIVE`], to report success.
+    pub fn success(self) -> Self {
+        self.colour(colours::css::POSITIVE)
+    }
+
+    /// Set the colour of the embed to the red of [`colours::css::DANGER`], to report an error.
+    pub fn error(self) -> Self {
+        self.colour(colours::css::DANGER)
+    }
+
+    /// Set the colour of the embed to the orange of [`colours::css::WARNING`], to report a
+    /// warning.
+    pub fn warning(self) -> Self {
+        self.colour(colours::css::WARNING)
+    }
+
+    /// Set the colour of the embed to the blurple of [`colours::branding::BLURPLE`], for
+    /// informational messages.
+    pub fn info(self) -> Self {
+        self.colour(colours::branding::BLURPLE)
+    }
+
     /// Set the description of the embed.
     ///
     /// **Note**: This can't be longer than 4096 characters.
@@ -92,6 +139,34 @@ impl CreateEmbed {
         self
     }
 
+    /// Adds multiple inline fields laid out in rows of the given number of columns, such as for a
+    /// leaderboard.
+    ///
+    /// The last row is completed with blank fields, so that the fields after it start on a row of
+    /// their own.
+    ///
+    /// **Note**: Discord displays at most 3 inline fields per row, and an embed can't have more
+    /// than 25 fields, including the blank ones.
+    pub fn inline_fields<N, V>(
+        mut self,
+        columns: usize,
+        items: impl IntoIterator<Item = (N, V)>,
+    ) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        let start = self.0.fields.len();
+        let items = items.into_iter().map(|(name, value)| EmbedField::new(name, value, true));
+        self.0.fields.extend(items);
+
+        let columns = columns.max(1);
+        let blanks = (columns - (self.0.fields.len() - start) % columns) % columns;
+        let blank = EmbedField::new("\u{200b}", "\u{200b}", true);
+        self.0.fields.extend(std::iter::repeat(blank).take(blanks));
+        self
+    }
+
     /// Set the footer of the embed.
     ///
     /// Refer to the documentation for [`CreateEmbedFooter`] for more information.
@@ -128,6 +203,8 @@ impl CreateEmbed {
     ///
     /// See the documentation of [`Timestamp`] for more information.
     ///
+    /// [`SystemTime`]: std::time::SystemTime
+    ///
     /// # Examples
     ///
     /// Passing a string timestamp:
@@ -139,6 +216,20 @@ impl CreateEmbed {
     /// let embed = CreateEmbed::new().title("hello").timestamp(timestamp);
     /// ```
     ///
+    /// Passing a [`SystemTime`] or a UNIX timestamp, which may be out of range:
+    ///
+    /// ```rust
+    /// # use std::time::SystemTime;
+    /// #
+    /// # use serenity::builder::CreateEmbed;
+    /// # use serenity::model::Timestamp;
+    /// let timestamp = Timestamp::try_from(SystemTime::now()).expect("Invalid timestamp!");
+    /// let embed = CreateEmbed::new().title("hello").timestamp(timestamp);
+    ///
+    /// let timestamp = Timestamp::try_from(1462015105_i64).expect("Invalid timestamp!");
+    /// let embed = CreateEmbed::new().title("hello").timestamp(timestamp);
+    /// ```
+    ///
     /// Creating a join-log:
     ///
     /// Note: this example isn't efficient and is for demonstrative purposes.
@@ -205,19 +296,95 @@ impl CreateEmbed {
 
     /// Same as calling [`Self::image`] with "attachment://filename.(jpg, png)".
     ///
-    /// Note however, you have to be sure you set an attachment (with [`ChannelId::send_files`])
-    /// with the provided filename. Or else this won't work.
+    /// Note however, you have to be sure you set an attachment (with [`ChannelId::send_files`] or
+    /// [`EditWebhookMessage::new_attachment`]) with the provided filename. Or else this won't
+    /// work.
+    ///
+    /// The filename must not contain a path separator, which is checked when the embed is sent.
     ///
     /// [`ChannelId::send_files`]: crate::model::id::ChannelId::send_files
+    /// [`EditWebhookMessage::new_attachment`]: super::EditWebhookMessage::new_attachment
     #[inline]
     pub fn attachment(self, filename: impl Into<String>) -> Self {
-        let mut filename = filename.into();
-        filename.insert_str(0, "attachment://");
-        self.image(filename)
+        self.image(attachment_url(filename.into()))
+    }
+
+    /// Same as calling [`Self::thumbnail`] with "attachment://filename.(jpg, png)".
+    ///
+    /// Refer to [`Self::attachment`] for more information.
+    #[inline]
+    pub fn attachment_thumbnail(self, filename: impl Into<String>) -> Self {
+        self.thumbnail(attachment_url(filename.into()))
+    }
+
+    /// Trims every part of the embed that is too long for Discord, ending it with an ellipsis,
+    /// instead of failing to send it.
+    ///
+    /// Each part is cut to its own limit, such as 4096 characters for the description and 1024
+    /// for a field value, and fields past the 25th are dropped. If the parts still add up to more
+    /// than 6000 characters, the description is trimmed further, then the field values from the
+    /// last one, then the footer, the field names, the title and the author's name. A part is
+    /// never trimmed further than its first character and the ellipsis, so none end up empty.
+    ///
+    /// Lengths are counted in characters, and parts are only ever cut between characters.
+    pub fn truncate(mut self) -> Self {
+        use crate::constants::*;
+
+        let embed = &mut self.0;
+        embed.fields.truncate(EMBED_FIELD_MAX_COUNT);
+
+        let (names, values): (Vec<_>, Vec<_>) =
+            embed.fields.iter_mut().rev().map(|field| (&mut field.name, &mut field.value)).unzip();
+        let title = embed.title.as_mut();
+        let author = embed.author.as_mut().map(|author| &mut author.name);
+        let footer = embed.footer.as_mut().map(|footer| &mut footer.text);
+
+        // Ordered by what to trim first when the total length is too large.
+        let mut parts = Vec::new();
+        parts.extend(embed.description.as_mut().map(|d| (d, EMBED_DESCRIPTION_MAX_LENGTH)));
+        parts.extend(values.into_iter().map(|value| (value, EMBED_FIELD_VALUE_MAX_LENGTH)));
+        parts.extend(footer.map(|text| (text, EMBED_FOOTER_MAX_LENGTH)));
+        parts.extend(names.into_iter().map(|name| (name, EMBED_FIELD_NAME_MAX_LENGTH)));
+        parts.extend(title.map(|title| (title, EMBED_TITLE_MAX_LENGTH)));
+        parts.extend(author.map(|name| (name, EMBED_TITLE_MAX_LENGTH)));
+
+        let mut length = 0;
+        for (part, max) in &mut parts {
+            truncate_chars(part, *max);
+            length += part.chars().count();
+        }
+
+        let mut excess = length.saturating_sub(EMBED_MAX_LENGTH);
+        for (part, _) in parts {
+            if excess == 0 {
+                break;
+            }
+
+            // Keep one character and the ellipsis, as Discord rejects empty field values.
+            let before = part.chars().count();
+            truncate_chars(part, before.saturating_sub(excess).max(before.min(2)));
+            excess -= before - part.chars().count();
+        }
+
+        self
     }
 
     #[cfg(feature = "http")]
     pub(super) fn check_length(&self) -> Result<()> {
+        let images = self.0.image.iter().map(|image| &image.url);
+        let thumbnails = self.0.thumbnail.iter().map(|thumbnail| &thumbnail.url);
+        for url in images.chain(thumbnails) {
+            if let Some(filename) = url.strip_prefix("attachment://") {
+                if filename.contains(['/', '\\']) {
+                    let filename = filename.to_string();
+                    return Err(Error::Model(ModelError::InvalidAttachmentFilename(filename)));
+                }
+            }
+        }
+
+        crate::utils::check_overflow(self.0.fields.len(), crate::constants::EMBED_FIELD_MAX_COUNT)
+            .map_err(|_| Error::Model(ModelError::EmbedFieldAmount))?;
+
         let mut length = 0;
         if let Some(ref author) = self.0.author {
             length += author.name.chars().count();
@@ -245,6 +412,25 @@ impl CreateEmbed {
     }
 }
 
+/// Trims `s` to at most `max` characters, ending it with an ellipsis if anything was cut.
+fn truncate_chars(s: &mut String, max: usize) {
+    if s.chars().count() <= max {
+        return;
+    }
+
+    // Leave room for the ellipsis, which is a single character.
+    let end = s.char_indices().nth(max.saturating_sub(1)).map_or(s.len(), |(i, _)| i);
+    s.truncate(end);
+    if max > 0 {
+        s.push('\u{2026}');
+    }
+}
+
+fn attachment_url(mut filename: String) -> String {
+    filename.insert_str(0, "attachment://");
+    filename
+}
+
 impl Default for CreateEmbed {
     /// Creates a builder with default values, setting the `type` to `rich`.
     fn default() -> Self {
@@ -348,3 +534,125 @@ impl From<EmbedFooter> for CreateEmbedFooter {
         Self(footer)
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "http")]
+mod tests {
+    use super::*;
+
+    fn embed(footer_len: usize) -> CreateEmbed {
+        CreateEmbed::new()
+            .title("t".repeat(256))
+            .description("d".repeat(4096))
+            .author(CreateEmbedAuthor::new("a".repeat(256)))
+            .field("n".repeat(256), "v".repeat(1024), false)
+            .footer(CreateEmbedFooter::new("f".repeat(footer_len)))
+    }
+
+    #[test]
+    fn combined_length_too_large() {
+        // Every part is within its own limit, but they add up to 6016 characters.
+        let err = embed(128).check_length().unwrap_err();
+        assert!(matches!(err, Error::Model(ModelError::EmbedTooLarge(16))));
+    }
+
+    #[test]
+    fn combined_length_within_limit() {
+        assert!(embed(111).check_length().is_ok());
+        assert!(embed(112).check_length().is_ok());
+        assert!(embed(113).check_length().is_err());
+    }
+
+    #[test]
+    fn inline_fields() {
+        let items = (1..=7).map(|i| (format!("#{i}"), format!("player {i}")));
+        let embed = CreateEmbed::new().inline_fields(3, items).field("total", "7", false);
+
+        let fields = embed.0.fields.iter().map(|f| (f.name.as_str(), f.inline)).collect::<Vec<_>>();
+        assert_eq!(fields, vec![
+            ("#1", true),
+            ("#2", true),
+            ("#3", true),
+            ("#4", true),
+            ("#5", true),
+            ("#6", true),
+            ("#7", true),
+            ("\u{200b}", true),
+            ("\u{200b}", true),
+            ("total", false),
+        ]);
+        assert_eq!(embed.0.fields[7].value, "\u{200b}");
+    }
+
+    #[test]
+    fn palette() {
+        assert_eq!(CreateEmbed::new().success().0.colour, Some(Colour::new(0x3BA55D)));
+        assert_eq!(CreateEmbed::new().error().0.colour, Some(Colour::new(0xED4245)));
+        assert_eq!(CreateEmbed::new().warning().0.colour, Some(Colour::new(0xFAA81A)));
+        assert_eq!(CreateEmbed::new().info().0.colour, Some(Colour::new(0x5865F2)));
+    }
+
+    #[test]
+    fn colour_hex() {
+        let embed = CreateEmbed::new().color_hex("#3498DB").unwrap();
+        assert_eq!(embed.0.colour, Some(Colour::BLUE));
+
+        let err = CreateEmbed::new().colour_hex("#3498D").unwrap_err();
+        assert!(matches!(err, Error::Model(ModelError::InvalidColour(hex)) if hex == "#3498D"));
+    }
+
+    #[test]
+    fn attachment() {
+        let embed = CreateEmbed::new().attachment("image.png").attachment_thumbnail("thumb.png");
+        assert_eq!(embed.0.image.as_ref().unwrap().url, "attachment://image.png");
+        assert_eq!(embed.0.thumbnail.as_ref().unwrap().url, "attachment://thumb.png");
+        assert!(embed.check_length().is_ok());
+
+        for filename in ["images/image.png", "images\\image.png"] {
+            let err = CreateEmbed::new().attachment_thumbnail(filename).check_length().unwrap_err();
+            assert!(matches!(
+                err,
+                Error::Model(ModelError::InvalidAttachmentFilename(name)) if name == filename
+            ));
+        }
+    }
+
+    #[test]
+    fn truncate_description() {
+        // Multi-byte characters, so that cutting at a byte offset would split one of them.
+        let embed = CreateEmbed::new().description("\u{e9}".repeat(5000)).truncate();
+
+        let description = embed.0.description.as_deref().unwrap();
+        assert_eq!(description.chars().count(), 4096);
+        assert!(description.ends_with('\u{2026}'));
+        assert!(description.starts_with(&"\u{e9}".repeat(4095)));
+        assert!(embed.check_length().is_ok());
+
+        let embed = CreateEmbed::new().description("short").truncate();
+        assert_eq!(embed.0.description.as_deref(), Some("short"));
+    }
+
+    #[test]
+    fn truncate_total() {
+        let embed = CreateEmbed::new()
+            .description("d".repeat(4096))
+            .inline_fields(3, (0..30).map(|i| (i.to_string(), "v".repeat(2000))))
+            .truncate();
+        assert_eq!(embed.0.fields.len(), 25);
+        assert!(embed.0.fields.iter().all(|field| field.value.chars().count() <= 1024));
+        assert!(embed.check_length().is_ok());
+
+        // The description is trimmed first to fit the total length, but never emptied.
+        assert_eq!(embed.0.description.as_deref(), Some("d\u{2026}"));
+        assert!(embed.0.fields.iter().all(|field| !field.value.is_empty()));
+    }
+
+    #[test]
+    fn too_many_fields() {
+        let embed = CreateEmbed::new().inline_fields(3, (0..25).map(|i| (i.to_string(), "v")));
+        assert!(matches!(embed.check_length(), Err(Error::Model(ModelError::EmbedFieldAmount))));
+
+        let embed = CreateEmbed::new().inline_fields(3, (0..24).map(|i| (i.to_string(), "v")));
+        assert!(embed.check_length().is_ok());
+    }
+}
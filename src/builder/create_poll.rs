@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+#[cfg(feature = "http")]
+use crate::internal::prelude::*;
+use crate::model::prelude::*;
+
+/// A builder for creating a poll in a message.
+///
+/// [Discord docs](https://discord.com/developers/docs/resources/poll#poll-create-request-object-poll-create-request-object-structure)
+#[derive(Clone, Debug, Serialize)]
+#[must_use]
+pub struct CreatePoll {
+    question: CreatePollMedia,
+    answers: Vec<CreatePollAnswer>,
+    duration: u16,
+    allow_multiselect: bool,
+}
+
+impl CreatePoll {
+    /// Creates a poll with the given question, no answers, a duration of 24 hours and a single
+    /// allowed choice.
+    pub fn new(question: impl Into<String>) -> Self {
+        Self {
+            question: CreatePollMedia::text(question),
+            answers: Vec::new(),
+            duration: 24,
+            allow_multiselect: false,
+        }
+    }
+
+    /// Adds an answer to the poll.
+    ///
+    /// **Note**: A poll can have up to 10 answers.
+    pub fn answer(mut self, answer: CreatePollAnswer) -> Self {
+        self.answers.push(answer);
+        self
+    }
+
+    /// Sets the answers of the poll, replacing any existing ones.
+    ///
+    /// **Note**: A poll can have up to 10 answers.
+    pub fn answers(mut self, answers: Vec<CreatePollAnswer>) -> Self {
+        self.answers = answers;
+        self
+    }
+
+    /// Sets how long the poll stays open. Discord only accepts whole hours, so the duration is
+    /// rounded down to the hour.
+    ///
+    /// **Note**: The maximum duration is 32 days.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = u16::try_from(duration.as_secs() / 3600).unwrap_or(u16::MAX);
+        self
+    }
+
+    /// Sets whether users can select more than one answer.
+    pub fn allow_multiselect(mut self, allow_multiselect: bool) -> Self {
+        self.allow_multiselect = allow_multiselect;
+        self
+    }
+
+    #[cfg(feature = "http")]
+    pub(super) fn check_length(&self) -> Result<()> {
+        crate::utils::check_overflow(self.answers.len(), crate::constants::POLL_ANSWER_MAX_COUNT)
+            .map_err(|_| Error::Model(ModelError::PollAnswerAmount))?;
+
+        if self.duration > crate::constants::POLL_DURATION_MAX_HOURS {
+            return Err(Error::Model(ModelError::PollDurationTooLong));
+        }
+
+        Ok(())
+    }
+}
+
+/// A builder for creating an answer of a poll. See [`CreatePoll::answer`].
+///
+/// [Discord docs](https://discord.com/developers/docs/resources/poll#poll-answer-object)
+#[derive(Clone, Debug, Serialize)]
+#[must_use]
+pub struct CreatePollAnswer {
+    poll_media: CreatePollMedia,
+}
+
+impl CreatePollAnswer {
+    /// Creates an answer with the given text.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            poll_media: CreatePollMedia::text(text),
+        }
+    }
+
+    /// Sets the emoji shown next to the answer.
+    pub fn emoji(mut self, emoji: impl Into<ReactionType>) -> Self {
+        self.poll_media.emoji = Some(match emoji.into() {
+            ReactionType::Custom {
+                id, ..
+            } => CreatePollEmoji {
+                id: Some(id),
+                name: None,
+            },
+            ReactionType::Unicode(name) => CreatePollEmoji {
+                id: None,
+                name: Some(name),
+            },
+        });
+        self
+    }
+}
+
+/// [Discord docs](https://discord.com/developers/docs/resources/poll#poll-media-object)
+#[derive(Clone, Debug, Serialize)]
+struct CreatePollMedia {
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    emoji: Option<CreatePollEmoji>,
+}
+
+impl CreatePollMedia {
+    fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            emoji: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct CreatePollEmoji {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<EmojiId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::{json, to_value};
+
+    #[test]
+    fn single_select_poll() {
+        let poll = CreatePoll::new("Favourite colour?")
+            .answers(vec![
+                CreatePollAnswer::new("Red"),
+                CreatePollAnswer::new("Green"),
+                CreatePollAnswer::new("Blue"),
+            ])
+            .answer(CreatePollAnswer::new("Yellow").emoji('\u{1f49b}'))
+            .duration(Duration::from_secs(2 * 24 * 3600));
+
+        assert_eq!(
+            to_value(poll).unwrap(),
+            json!({
+                "question": {"text": "Favourite colour?"},
+                "answers": [
+                    {"poll_media": {"text": "Red"}},
+                    {"poll_media": {"text": "Green"}},
+                    {"poll_media": {"text": "Blue"}},
+                    {"poll_media": {"text": "Yellow", "emoji": {"name": "\u{1f49b}"}}},
+                ],
+                "duration": 48,
+                "allow_multiselect": false,
+            })
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn check_length() {
+        let answers = |count| (0..count).map(|i| CreatePollAnswer::new(i.to_string())).collect();
+        let poll = CreatePoll::new("?").answers(answers(10));
+        assert!(poll.check_length().is_ok());
+
+        let err = poll.clone().answer(CreatePollAnswer::new("11")).check_length().unwrap_err();
+        assert!(matches!(err, Error::Model(ModelError::PollAnswerAmount)));
+
+        let days = |days: u64| Duration::from_secs(days * 24 * 3600);
+        assert!(poll.clone().duration(days(32)).check_length().is_ok());
+        let err = poll.duration(days(33)).check_length().unwrap_err();
+        assert!(matches!(err, Error::Model(ModelError::PollDurationTooLong)));
+    }
+}
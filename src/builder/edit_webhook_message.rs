@@ -5,11 +5,13 @@ use super::{
     CreateAllowedMentions,
     CreateAttachment,
     CreateEmbed,
-    ExistingAttachment,
+    NewOrExisting,
 };
 #[cfg(feature = "http")]
 use crate::constants;
 #[cfg(feature = "http")]
+use crate::http::retry_on_ratelimit;
+#[cfg(feature = "http")]
 use crate::http::CacheHttp;
 #[cfg(feature = "http")]
 use crate::internal::prelude::*;
@@ -24,7 +26,7 @@ use crate::utils::check_overflow;
 #[must_use]
 pub struct EditWebhookMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
-    content: Option<String>,
+    content: Option<Option<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     embeds: Option<Vec<CreateEmbed>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -32,12 +34,24 @@ pub struct EditWebhookMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) components: Option<Vec<CreateActionRow>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    attachments: Option<Vec<ExistingAttachment>>,
+    attachments: Option<Vec<NewOrExisting>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flags: Option<MessageFlags>,
 
     #[serde(skip)]
     thread_id: Option<ChannelId>,
     #[serde(skip)]
     pub(crate) files: Vec<CreateAttachment>,
+    /// Filenames of existing attachments to keep, along with the index in [`Self::attachments`]
+    /// they are inserted at once resolved.
+    #[serde(skip)]
+    attachment_names: Vec<(usize, String)>,
+    #[serde(skip)]
+    attachment_size_limit: Option<u64>,
+    #[serde(skip)]
+    ratelimit_retries: u8,
+    #[serde(skip)]
+    disable_existing_components: bool,
 }
 
 impl EditWebhookMessage {
@@ -48,7 +62,7 @@ impl EditWebhookMessage {
 
     #[cfg(feature = "http")]
     pub(crate) fn check_length(&self) -> Result<()> {
-        if let Some(content) = &self.content {
+        if let Some(Some(content)) = &self.content {
             check_overflow(content.chars().count(), constants::MESSAGE_CODE_LIMIT)
                 .map_err(|overflow| Error::Model(ModelError::MessageTooLong(overflow)))?;
         }
@@ -61,6 +75,35 @@ impl EditWebhookMessage {
             }
         }
 
+        if let Some(components) = &self.components {
+            check_overflow(components.len(), constants::ACTION_ROW_MAX_COUNT)
+                .map_err(|_| Error::Model(ModelError::ActionRowAmount))?;
+            for row in components {
+                // Select menus and input texts always occupy a full row on their own.
+                if let CreateActionRow::Buttons(buttons) = row {
+                    check_overflow(buttons.len(), constants::BUTTON_MAX_COUNT)
+                        .map_err(|_| Error::Model(ModelError::ButtonAmount))?;
+                }
+            }
+        }
+
+        if let Some(flags) = self.flags {
+            let disallowed =
+                flags - (MessageFlags::SUPPRESS_EMBEDS | MessageFlags::SUPPRESS_NOTIFICATIONS);
+            if !disallowed.is_empty() {
+                return Err(Error::Model(ModelError::InvalidMessageFlags(disallowed)));
+            }
+        }
+
+        let total = self.files.iter().map(CreateAttachment::size).sum();
+        let limit = self.attachment_size_limit.unwrap_or(constants::ATTACHMENT_SIZE_LIMIT);
+        if total > limit {
+            return Err(Error::Model(ModelError::AttachmentTooLarge {
+                total,
+                limit,
+            }));
+        }
+
         Ok(())
     }
 
@@ -69,7 +112,16 @@ impl EditWebhookMessage {
     /// **Note**: Message contents must be under 2000 unicode code points.
     #[inline]
     pub fn content(mut self, content: impl Into<String>) -> Self {
-        self.content = Some(content.into());
+        self.content = Some(Some(content.into()));
+        self
+    }
+
+    /// Removes the content of the message.
+    ///
+    /// The message must still have an embed, attachment or component left after the edit.
+    #[inline]
+    pub fn clear_content(mut self) -> Self {
+        self.content = Some(None);
         self
     }
 
@@ -111,12 +163,20 @@ impl EditWebhookMessage {
     /// **Note**: You can only have up to 10 embeds per message.
     ///
     /// Calling this will overwrite the embed list. To append embeds, call [`Self::add_embeds`]
-    /// instead.
+    /// instead. Passing an empty list removes all embeds, same as [`Self::clear_embeds`].
     pub fn embeds(mut self, embeds: Vec<CreateEmbed>) -> Self {
         self.embeds = Some(embeds);
         self
     }
 
+    /// Removes all embeds from the message.
+    ///
+    /// The message must still have content, an attachment or a component left after the edit.
+    pub fn clear_embeds(mut self) -> Self {
+        self.embeds = Some(Vec::new());
+        self
+    }
+
     /// Set the allowed mentions for the message.
     pub fn allowed_mentions(mut self, allowed_mentions: CreateAllowedMentions) -> Self {
         self.allowed_mentions = Some(allowed_mentions);
@@ -127,6 +187,10 @@ impl EditWebhookMessage {
     /// the webhook's `kind` field is set to [`WebhookType::Application`], or it was created by an
     /// application (and has kind [`WebhookType::Incoming`]).
     ///
+    ///
+    /// If this is never called, the components of the message are left untouched. Passing an
+    /// empty list removes all components, same as [`Self::clear_components`].
+    ///
     /// [`WebhookType::Application`]: crate::model::webhook::WebhookType
     /// [`WebhookType::Incoming`]: crate::model::webhook::WebhookType
     pub fn components(mut self, components: Vec<CreateActionRow>) -> Self {
@@ -135,6 +199,28 @@ impl EditWebhookMessage {
     }
     super::button_and_select_menu_convenience_methods!(self.components);
 
+    /// Removes all components from the message.
+    pub fn clear_components(mut self) -> Self {
+        self.components = Some(Vec::new());
+        self
+    }
+
+    /// Disables every button and select menu of the message, such as to lock it once it should no
+    /// longer be interacted with.
+    ///
+    /// If components were set with [`Self::components`], those are disabled. Otherwise, the
+    /// current components of the message are kept but disabled, which fetches the message with an
+    /// additional request when the builder is executed.
+    pub fn disable_all_components(mut self) -> Self {
+        match self.components.take() {
+            Some(rows) => {
+                self.components = Some(rows.into_iter().map(CreateActionRow::disable_all).collect());
+            },
+            None => self.disable_existing_components = true,
+        }
+        self
+    }
+
     /// Add a new attachment for the message.
     ///
     /// This can be called multiple times.
@@ -150,9 +236,78 @@ impl EditWebhookMessage {
     ///
     /// To be used after [`Self::new_attachment`] or [`Self::clear_existing_attachments`].
     pub fn keep_existing_attachment(mut self, id: AttachmentId) -> Self {
-        self.attachments.get_or_insert_with(Vec::new).push(ExistingAttachment {
-            id,
-        });
+        self.attachments.get_or_insert_with(Vec::new).push(NewOrExisting::Existing(id));
+        self
+    }
+
+    /// Keeps several existing attachments by id, in the given order.
+    ///
+    /// To be used after [`Self::new_attachment`] or [`Self::clear_existing_attachments`].
+    pub fn keep_existing_attachments(
+        mut self,
+        ids: impl IntoIterator<Item = AttachmentId>,
+    ) -> Self {
+        let attachments = self.attachments.get_or_insert_with(Vec::new);
+        attachments.extend(ids.into_iter().map(NewOrExisting::Existing));
+        self
+    }
+
+    /// Keeps an existing attachment by filename, for when its id is not at hand.
+    ///
+    /// The id is resolved when the builder is executed, which fetches the message with an
+    /// additional request.
+    ///
+    /// To be used after [`Self::new_attachment`] or [`Self::clear_existing_attachments`].
+    pub fn keep_existing_attachment_by_name(mut self, name: impl Into<String>) -> Self {
+        let index = self.attachments.get_or_insert_with(Vec::new).len();
+        self.attachment_names.push((index, name.into()));
+        self
+    }
+
+    /// Replaces the filenames given to [`Self::keep_existing_attachment_by_name`] with the ids of
+    /// the matching attachments of the message.
+    #[cfg(feature = "http")]
+    fn resolve_attachment_names(&mut self, existing: &[Attachment]) -> Result<()> {
+        let Some(attachments) = &mut self.attachments else { return Ok(()) };
+
+        // Going backwards keeps the indices of the earlier names valid.
+        for (index, name) in self.attachment_names.drain(..).rev() {
+            let mut matching = existing.iter().filter(|attachment| attachment.filename == name);
+            let id = match (matching.next(), matching.next()) {
+                (Some(attachment), None) => attachment.id,
+                (Some(_), Some(_)) => {
+                    return Err(Error::Model(ModelError::AmbiguousAttachment(name)))
+                },
+                (None, _) => return Err(Error::Model(ModelError::AttachmentNotFound(name))),
+            };
+
+            attachments.insert(index, NewOrExisting::Existing(id));
+        }
+
+        Ok(())
+    }
+
+    /// Sets the maximum total size in bytes of the new attachments, which is checked before
+    /// uploading them.
+    ///
+    /// Defaults to [`constants::ATTACHMENT_SIZE_LIMIT`], and should be raised for boosted guilds,
+    /// which allow larger uploads.
+    ///
+    /// [`constants::ATTACHMENT_SIZE_LIMIT`]: crate::constants::ATTACHMENT_SIZE_LIMIT
+    pub fn attachment_size_limit(mut self, limit: u64) -> Self {
+        self.attachment_size_limit = Some(limit);
+        self
+    }
+
+    /// Retries the edit up to the given number of times when it is ratelimited, waiting for as long
+    /// as each response asks in between. By default, the edit is not retried.
+    ///
+    /// This is for when the ratelimiter of the [`Http`] client is disabled, such as behind a proxy,
+    /// in which case ratelimited responses are returned as errors instead of waited out.
+    ///
+    /// [`Http`]: crate::http::Http
+    pub fn retry_on_ratelimit(mut self, retries: u8) -> Self {
+        self.ratelimit_retries = retries;
         self
     }
 
@@ -162,6 +317,28 @@ impl EditWebhookMessage {
     /// keep only some existing attachments.
     pub fn clear_existing_attachments(mut self) -> Self {
         self.attachments = Some(Vec::new());
+        self.attachment_names.clear();
+        self
+    }
+
+    /// Sets the order of the attachments of the message, overwriting any existing attachments
+    /// kept with [`Self::keep_existing_attachment`].
+    ///
+    /// [`NewOrExisting::New`] refers to an attachment added with [`Self::new_attachment`] by its
+    /// index, in the order they were added. Existing attachments not listed will be removed.
+    pub fn attachment_order(mut self, order: Vec<NewOrExisting>) -> Self {
+        self.attachments = Some(order);
+        self.attachment_names.clear();
+        self
+    }
+
+    /// Sets the flags for the message.
+    ///
+    /// **Note**: Only [`MessageFlags::SUPPRESS_EMBEDS`] and
+    /// [`MessageFlags::SUPPRESS_NOTIFICATIONS`] can be set when editing a webhook message. Any
+    /// other flag will result in an error when the builder is executed.
+    pub fn flags(mut self, flags: MessageFlags) -> Self {
+        self.flags = Some(flags);
         self
     }
 }
@@ -179,22 +356,271 @@ impl Builder for EditWebhookMessage {
     ///
     /// # Errors
     ///
-    /// Returns an [`Error::Model`] if the message content is too long.
+    /// Returns an [`Error::Model`] if the message content is too long, if there are too many
+    /// action rows or buttons, if flags other than [`MessageFlags::SUPPRESS_EMBEDS`] and
+    /// [`MessageFlags::SUPPRESS_NOTIFICATIONS`] are set, or if the new attachments are larger than
+    /// the [size limit].
     ///
     /// May also return an [`Error::Http`] if the content is malformed, the webhook's token is
     /// invalid, or the given message Id does not belong to the webhook.
     ///
+    /// If [`Self::retry_on_ratelimit`] was set, ratelimited edits are retried before an error is
+    /// returned.
+    ///
+    /// Returns [`ModelError::AttachmentNotFound`] or [`ModelError::AmbiguousAttachment`] if an
+    /// attachment kept by name does not match exactly one attachment of the message.
+    ///
     /// Or may return an [`Error::Json`] if there is an error deserialising Discord's response.
+    ///
+    /// [size limit]: Self::attachment_size_limit
     async fn execute(
         mut self,
         cache_http: impl CacheHttp,
         ctx: Self::Context<'_>,
     ) -> Result<Self::Built> {
         self.check_length()?;
-        let files = std::mem::take(&mut self.files);
-        cache_http
-            .http()
-            .edit_webhook_message(ctx.0, self.thread_id, ctx.1, ctx.2, &self, files)
-            .await
+
+        let disable_existing_components =
+            self.disable_existing_components && self.components.is_none();
+        if !self.attachment_names.is_empty() || disable_existing_components {
+            let http = cache_http.http();
+            let message = http.get_webhook_message(ctx.0, self.thread_id, ctx.1, ctx.2).await?;
+            self.resolve_attachment_names(&message.attachments)?;
+
+            if disable_existing_components {
+                let rows = message.components.into_iter().map(CreateActionRow::from);
+                self.components = Some(rows.map(CreateActionRow::disable_all).collect());
+            }
+        }
+
+        let mut files = std::mem::take(&mut self.files);
+        let http = cache_http.http();
+        let this = &self;
+        retry_on_ratelimit(self.ratelimit_retries, move |last| {
+            // Only keep a copy of the files while they may have to be sent again.
+            let files = if last { std::mem::take(&mut files) } else { files.clone() };
+            http.edit_webhook_message(ctx.0, this.thread_id, ctx.1, ctx.2, this, files)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "http")]
+mod tests {
+    use super::*;
+    use crate::builder::{CreateButton, CreateSelectMenu, CreateSelectMenuKind};
+
+    fn buttons(n: usize) -> CreateActionRow {
+        CreateActionRow::Buttons((0..n).map(|i| CreateButton::new(i.to_string())).collect())
+    }
+
+    #[test]
+    fn too_many_action_rows() {
+        let builder = EditWebhookMessage::new().components(vec![buttons(1); 6]);
+        let err = builder.check_length().unwrap_err();
+        assert!(matches!(err, Error::Model(ModelError::ActionRowAmount)));
+    }
+
+    #[test]
+    fn too_many_buttons() {
+        let builder = EditWebhookMessage::new().components(vec![buttons(5), buttons(6)]);
+        let err = builder.check_length().unwrap_err();
+        assert!(matches!(err, Error::Model(ModelError::ButtonAmount)));
+
+        let builder = EditWebhookMessage::new().components(vec![buttons(5); 5]);
+        assert!(builder.check_length().is_ok());
+    }
+
+    #[test]
+    fn clear_content() {
+        let builder = EditWebhookMessage::new().content("foo").clear_content();
+        assert!(builder.check_length().is_ok());
+
+        let json = crate::json::to_value(&builder).unwrap();
+        assert_eq!(json, crate::json::json!({"content": null}));
+
+        let json = crate::json::to_value(EditWebhookMessage::new()).unwrap();
+        assert_eq!(json, crate::json::json!({}));
+    }
+
+    #[test]
+    fn clear_embeds() {
+        let builder = EditWebhookMessage::new().embed(CreateEmbed::new()).clear_embeds();
+        assert!(builder.check_length().is_ok());
+
+        let json = crate::json::to_value(&builder).unwrap();
+        assert_eq!(json, crate::json::json!({"embeds": []}));
+
+        let json = crate::json::to_value(EditWebhookMessage::new().embeds(vec![])).unwrap();
+        assert_eq!(json, crate::json::json!({"embeds": []}));
+    }
+
+    #[test]
+    fn clear_components() {
+        let builder = EditWebhookMessage::new().components(vec![buttons(1)]).clear_components();
+        assert!(builder.check_length().is_ok());
+
+        let json = crate::json::to_value(&builder).unwrap();
+        assert_eq!(json, crate::json::json!({"components": []}));
+    }
+
+    #[test]
+    fn disable_all_components() {
+        let select_menu = CreateSelectMenu::new("select", CreateSelectMenuKind::User);
+        let builder = EditWebhookMessage::new()
+            .components(vec![buttons(3), CreateActionRow::SelectMenu(select_menu)])
+            .disable_all_components();
+        assert!(!builder.disable_existing_components);
+
+        let json = crate::json::to_value(&builder).unwrap();
+        let rows = json["components"].as_array().unwrap();
+        assert_eq!(rows.len(), 2);
+        for component in rows.iter().flat_map(|row| row["components"].as_array().unwrap()) {
+            assert_eq!(component["disabled"], crate::json::json!(true));
+        }
+
+        let builder = EditWebhookMessage::new().disable_all_components();
+        assert!(builder.disable_existing_components);
+        assert!(builder.components.is_none());
+    }
+
+    #[test]
+    fn attachment_order() {
+        let builder = EditWebhookMessage::new()
+            .new_attachment(CreateAttachment::bytes(vec![0], "a.png"))
+            .new_attachment(CreateAttachment::bytes(vec![1], "b.png"))
+            .attachment_order(vec![
+                NewOrExisting::New(0),
+                NewOrExisting::Existing(AttachmentId::new(42)),
+                NewOrExisting::New(1),
+            ]);
+
+        let json = crate::json::to_value(&builder).unwrap();
+        assert_eq!(
+            json,
+            crate::json::json!({"attachments": [{"id": 0}, {"id": "42"}, {"id": 1}]})
+        );
+    }
+
+    #[test]
+    fn keep_existing_attachments() {
+        let builder = EditWebhookMessage::new()
+            .clear_existing_attachments()
+            .keep_existing_attachments([5, 3, 1, 4, 2].map(AttachmentId::new));
+
+        let json = crate::json::to_value(&builder).unwrap();
+        assert_eq!(
+            json,
+            crate::json::json!({"attachments": [
+                {"id": "5"},
+                {"id": "3"},
+                {"id": "1"},
+                {"id": "4"},
+                {"id": "2"},
+            ]})
+        );
+    }
+
+    fn attachment(id: u64, filename: &str) -> Attachment {
+        crate::json::from_value(crate::json::json!({
+            "id": id.to_string(),
+            "filename": filename,
+            "proxy_url": "",
+            "size": 0,
+            "url": "",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn keep_existing_attachment_by_name() {
+        let existing = [attachment(1, "a.png"), attachment(2, "b.png"), attachment(3, "c.png")];
+
+        let mut builder = EditWebhookMessage::new()
+            .clear_existing_attachments()
+            .keep_existing_attachment_by_name("c.png")
+            .keep_existing_attachment(AttachmentId::new(2))
+            .keep_existing_attachment_by_name("a.png");
+        builder.resolve_attachment_names(&existing).unwrap();
+
+        let json = crate::json::to_value(&builder).unwrap();
+        assert_eq!(
+            json,
+            crate::json::json!({"attachments": [{"id": "3"}, {"id": "2"}, {"id": "1"}]})
+        );
+    }
+
+    #[test]
+    fn keep_existing_attachment_by_ambiguous_name() {
+        let existing = [attachment(1, "a.png"), attachment(2, "a.png")];
+
+        let mut builder = EditWebhookMessage::new().keep_existing_attachment_by_name("a.png");
+        let err = builder.resolve_attachment_names(&existing).unwrap_err();
+        assert!(
+            matches!(err, Error::Model(ModelError::AmbiguousAttachment(name)) if name == "a.png")
+        );
+
+        let mut builder = EditWebhookMessage::new().keep_existing_attachment_by_name("b.png");
+        let err = builder.resolve_attachment_names(&existing).unwrap_err();
+        assert!(
+            matches!(err, Error::Model(ModelError::AttachmentNotFound(name)) if name == "b.png")
+        );
+    }
+
+    #[test]
+    fn flags_allowed() {
+        let builder = EditWebhookMessage::new()
+            .flags(MessageFlags::SUPPRESS_EMBEDS | MessageFlags::SUPPRESS_NOTIFICATIONS);
+        assert!(builder.check_length().is_ok());
+
+        let json = crate::json::to_value(&builder).unwrap();
+        assert_eq!(json["flags"], crate::json::json!((1 << 2) | (1 << 12)));
+    }
+
+    #[test]
+    fn flags_skipped_when_unset() {
+        let json = crate::json::to_value(EditWebhookMessage::new()).unwrap();
+        assert!(json.get("flags").is_none());
+    }
+
+    #[test]
+    fn flags_disallowed() {
+        let builder = EditWebhookMessage::new()
+            .flags(MessageFlags::SUPPRESS_EMBEDS | MessageFlags::EPHEMERAL);
+        let err = builder.check_length().unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Model(ModelError::InvalidMessageFlags(flags)) if flags == MessageFlags::EPHEMERAL
+        ));
+    }
+
+    #[test]
+    fn attachments_under_size_limit() {
+        let data = vec![0; 12 * 1024 * 1024];
+        let builder = EditWebhookMessage::new()
+            .new_attachment(CreateAttachment::bytes(data.clone(), "a.bin"))
+            .new_attachment(CreateAttachment::bytes(data, "b.bin"));
+        assert!(builder.check_length().is_ok());
+    }
+
+    #[test]
+    fn attachments_over_size_limit() {
+        let data = vec![0; 13 * 1024 * 1024];
+        let builder = EditWebhookMessage::new()
+            .new_attachment(CreateAttachment::bytes(data.clone(), "a.bin"))
+            .new_attachment(CreateAttachment::bytes(data, "b.bin"));
+        let err = builder.clone().check_length().unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Model(ModelError::AttachmentTooLarge {
+                total: 27_262_976,
+                limit: constants::ATTACHMENT_SIZE_LIMIT,
+            })
+        ));
+
+        // Boosted guilds allow larger uploads.
+        let builder = builder.attachment_size_limit(50 * 1024 * 1024);
+        assert!(builder.check_length().is_ok());
     }
 }
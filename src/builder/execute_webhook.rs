@@ -1,6 +1,12 @@
 #[cfg(feature = "http")]
 use super::Builder;
-use super::{CreateActionRow, CreateAllowedMentions, CreateAttachment, CreateEmbed};
+use super::{
+    CreateActionRow,
+    CreateAllowedMentions,
+    CreateAttachment,
+    CreateEmbed,
+    CreatePoll,
+};
 #[cfg(feature = "http")]
 use crate::constants;
 #[cfg(feature = "http")]
@@ -71,6 +77,8 @@ pub struct ExecuteWebhook {
     flags: Option<MessageFlags>,
     #[serde(skip_serializing_if = "Option::is_none")]
     thread_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    poll: Option<CreatePoll>,
 
     #[serde(skip)]
     thread_id: Option<ChannelId>,
@@ -97,11 +105,31 @@ impl ExecuteWebhook {
             embed.check_length()?;
         }
 
+        if let Some(poll) = &self.poll {
+            poll.check_length()?;
+        }
+
+        if let Some(username) = &self.username {
+            if username.is_empty() {
+                return Err(Error::Model(ModelError::NameTooShort));
+            }
+            check_overflow(username.chars().count(), constants::WEBHOOK_USERNAME_MAX_LENGTH)
+                .map_err(|_| Error::Model(ModelError::NameTooLong))?;
+        }
+
         Ok(())
     }
 
+    /// Whether the username or avatar of the webhook is overridden.
+    #[cfg(feature = "http")]
+    pub(crate) fn overrides_identity(&self) -> bool {
+        self.username.is_some() || self.avatar_url.is_some()
+    }
+
     /// Override the default avatar of the webhook with an image URL.
     ///
+    /// This is only supported by incoming webhooks.
+    ///
     /// # Examples
     ///
     /// Overriding the default avatar:
@@ -270,6 +298,8 @@ impl ExecuteWebhook {
 
     /// Override the default username of the webhook.
     ///
+    /// This is only supported by incoming webhooks, and must be between 1 and 80 characters.
+    ///
     /// # Examples
     ///
     /// Overriding the username to `"hakase"`:
@@ -332,6 +362,14 @@ impl ExecuteWebhook {
         self.thread_name = Some(thread_name);
         self
     }
+
+    /// Adds a poll to the message. Requires an application-owned webhook.
+    ///
+    /// **Note**: Polls can't be added to or changed on an existing message.
+    pub fn poll(mut self, poll: CreatePoll) -> Self {
+        self.poll = Some(poll);
+        self
+    }
 }
 
 #[cfg(feature = "http")]
@@ -344,6 +382,9 @@ impl Builder for ExecuteWebhook {
     ///
     /// # Errors
     ///
+    /// Returns an [`Error::Model`] if the message content is too long, if there are too many
+    /// embeds, or if the overridden username is empty or too long.
+    ///
     /// Returns [`Error::Http`] if the content is malformed, if the token is invalid, or if
     /// execution is attempted in a thread not belonging to the webhook's [`Channel`].
     ///
@@ -358,3 +399,50 @@ impl Builder for ExecuteWebhook {
         cache_http.http().execute_webhook(ctx.0, self.thread_id, ctx.1, ctx.2, files, &self).await
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "http")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn username_length() {
+        let builder = ExecuteWebhook::new().username("hakase");
+        assert!(builder.check_length().is_ok());
+
+        let err = ExecuteWebhook::new().username("").check_length().unwrap_err();
+        assert!(matches!(err, Error::Model(ModelError::NameTooShort)));
+
+        let builder = ExecuteWebhook::new().username("a".repeat(80));
+        assert!(builder.check_length().is_ok());
+
+        let err = ExecuteWebhook::new().username("a".repeat(81)).check_length().unwrap_err();
+        assert!(matches!(err, Error::Model(ModelError::NameTooLong)));
+    }
+
+    #[test]
+    fn identity_overrides() {
+        let builder = ExecuteWebhook::new();
+        assert!(!builder.overrides_identity());
+
+        let builder = builder.username("hakase").avatar_url("https://i.imgur.com/KTs6whd.jpg");
+        assert!(builder.overrides_identity());
+
+        let json = crate::json::to_value(&builder).unwrap();
+        assert_eq!(
+            json,
+            crate::json::json!({
+                "tts": false,
+                "embeds": [],
+                "username": "hakase",
+                "avatar_url": "https://i.imgur.com/KTs6whd.jpg",
+            })
+        );
+    }
+
+    #[test]
+    fn tts() {
+        let json = crate::json::to_value(ExecuteWebhook::new().content("hello").tts(true)).unwrap();
+        assert_eq!(json, crate::json::json!({"content": "hello", "tts": true, "embeds": []}));
+    }
+}
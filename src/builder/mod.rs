@@ -42,6 +42,7 @@ mod create_interaction_response;
 mod create_interaction_response_followup;
 mod create_invite;
 mod create_message;
+mod create_poll;
 mod create_scheduled_event;
 mod create_stage_instance;
 mod create_sticker;
@@ -82,6 +83,7 @@ pub use create_interaction_response::*;
 pub use create_interaction_response_followup::*;
 pub use create_invite::*;
 pub use create_message::*;
+pub use create_poll::*;
 pub use create_scheduled_event::*;
 pub use create_stage_instance::*;
 pub use create_sticker::*;
@@ -385,10 +385,19 @@ impl IntoFuture for ClientBuilder {
                 #[cfg(feature = "voice")]
                 voice_manager: voice_manager.as_ref().map(Arc::clone),
                 ws_url: Arc::clone(&ws_url),
+                ws_url_fn: None,
                 #[cfg(feature = "cache")]
                 cache: Arc::clone(&cache),
                 http: Arc::clone(&http),
                 intents,
+                intents_fn: None,
+                boot_strategy: None,
+                pre_identify: None,
+                boot_timings: None,
+                ratelimit_waits: None,
+                boot_retries: None,
+                queue_limit: None,
+                boot_jitter: None,
                 presence: Some(presence),
             });
 
@@ -6,9 +6,52 @@ pub const EMBED_MAX_LENGTH: usize = 6000;
 /// The maximum number of embeds in a message.
 pub const EMBED_MAX_COUNT: usize = 10;
 
+/// The maximum number of fields in an embed.
+pub const EMBED_FIELD_MAX_COUNT: usize = 25;
+
+/// The maximum length of the title of an embed, and of the name of its author.
+pub const EMBED_TITLE_MAX_LENGTH: usize = 256;
+
+/// The maximum length of the description of an embed.
+pub const EMBED_DESCRIPTION_MAX_LENGTH: usize = 4096;
+
+/// The maximum length of the name of an embed field.
+pub const EMBED_FIELD_NAME_MAX_LENGTH: usize = 256;
+
+/// The maximum length of the value of an embed field.
+pub const EMBED_FIELD_VALUE_MAX_LENGTH: usize = 1024;
+
+/// The maximum length of the footer text of an embed.
+pub const EMBED_FOOTER_MAX_LENGTH: usize = 2048;
+
 /// The maximum number of stickers in a message.
 pub const STICKER_MAX_COUNT: usize = 3;
 
+/// The maximum number of answers in a poll.
+pub const POLL_ANSWER_MAX_COUNT: usize = 10;
+
+/// The maximum duration of a poll, in hours.
+pub const POLL_DURATION_MAX_HOURS: u16 = 32 * 24;
+
+/// The maximum length of the username a webhook message is sent with.
+pub const WEBHOOK_USERNAME_MAX_LENGTH: usize = 80;
+
+/// The maximum number of action rows in a message.
+pub const ACTION_ROW_MAX_COUNT: usize = 5;
+
+/// The maximum number of buttons in an action row.
+pub const BUTTON_MAX_COUNT: usize = 5;
+
+/// The maximum total size in bytes of the attachments uploaded with a message, unless the guild is
+/// boosted.
+pub const ATTACHMENT_SIZE_LIMIT: u64 = 25 * 1024 * 1024;
+
+/// The maximum size in bytes of a file downloaded by [`CreateAttachment::url`], which is the
+/// largest upload allowed in any guild.
+///
+/// [`CreateAttachment::url`]: crate::builder::CreateAttachment::url
+pub const ATTACHMENT_DOWNLOAD_LIMIT: u64 = 500 * 1024 * 1024;
+
 /// The gateway version used by the library. The gateway URL is retrieved via the REST API.
 pub const GATEWAY_VERSION: u8 = 10;
 
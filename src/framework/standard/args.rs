@@ -185,6 +185,18 @@ fn lex(stream: &mut Stream<'_>, delims: &[Cow<'_, str>]) -> Option<Token> {
     Some(Token::new(TokenKind::Argument, start, end))
 }
 
+/// Returns the delimiters that occur in the message, as only those can separate arguments.
+fn used_delimiters<'a>(message: &str, possible_delimiters: &'a [Delimiter]) -> Vec<Cow<'a, str>> {
+    possible_delimiters
+        .iter()
+        .filter(|d| match d {
+            Delimiter::Single(c) => message.contains(*c),
+            Delimiter::Multiple(s) => message.contains(s),
+        })
+        .map(Delimiter::to_str)
+        .collect()
+}
+
 fn is_surrounded_with(s: &str, begin: char, end: char) -> bool {
     s.starts_with(begin) && s.ends_with(end)
 }
@@ -320,6 +332,7 @@ pub struct Args {
     args: Vec<Token>,
     offset: usize,
     state: State,
+    prefix_len: usize,
 }
 
 impl Args {
@@ -350,14 +363,7 @@ impl Args {
     /// ```
     #[must_use]
     pub fn new(message: &str, possible_delimiters: &[Delimiter]) -> Self {
-        let delims = possible_delimiters
-            .iter()
-            .filter(|d| match d {
-                Delimiter::Single(c) => message.contains(*c),
-                Delimiter::Multiple(s) => message.contains(s),
-            })
-            .map(Delimiter::to_str)
-            .collect::<Vec<_>>();
+        let delims = used_delimiters(message, possible_delimiters);
 
         let args = if delims.is_empty() {
             let msg = message.trim();
@@ -391,9 +397,17 @@ impl Args {
             message: message.to_string(),
             offset: 0,
             state: State::None,
+            prefix_len: 0,
         }
     }
 
+    /// Sets the length of the prefix the command was invoked with.
+    #[must_use]
+    pub(crate) fn with_prefix_len(mut self, prefix_len: usize) -> Self {
+        self.prefix_len = prefix_len;
+        self
+    }
+
     #[inline]
     fn span(&self) -> (usize, usize) {
         self.args[self.offset].span
@@ -857,6 +871,16 @@ impl Args {
         &self.message
     }
 
+    /// Get the number of bytes the prefix took up in the message the command was invoked with,
+    /// including the whitespace after it, such as 5 for `<@1> ping`.
+    ///
+    /// This is `0` when there was no prefix, and for arguments not created by the framework.
+    #[inline]
+    #[must_use]
+    pub fn prefix_len(&self) -> usize {
+        self.prefix_len
+    }
+
     /// Starting from the offset, return the remainder of available arguments.
     #[inline]
     #[must_use]
@@ -996,3 +1020,90 @@ impl<'a> Iterator for RawArguments<'a> {
         Some(s)
     }
 }
+
+/// Iterates over the arguments of a message as they are split, without collecting them into an
+/// [`Args`] first.
+///
+/// Arguments are split the same way as by [`Args::new`], including arguments in quotes.
+///
+/// # Examples
+///
+/// ```rust
+/// use serenity::framework::standard::{Delimiter, Tokens};
+///
+/// let delimiters = [Delimiter::Single(' ')];
+/// let mut tokens = Tokens::new("play \"Never Gonna Give You Up\" loud", &delimiters);
+///
+/// assert_eq!(tokens.next(), Some("play"));
+/// assert_eq!(tokens.next(), Some("\"Never Gonna Give You Up\""));
+/// assert_eq!(tokens.next(), Some("loud"));
+/// assert_eq!(tokens.next(), None);
+/// ```
+pub struct Tokens<'a> {
+    message: &'a str,
+    stream: Stream<'a>,
+    delims: Vec<Cow<'a, str>>,
+    quoted: bool,
+}
+
+impl<'a> Tokens<'a> {
+    /// Creates an iterator over the arguments in `message`, separated by any of the
+    /// `possible_delimiters`.
+    #[must_use]
+    pub fn new(message: &'a str, possible_delimiters: &'a [Delimiter]) -> Self {
+        let delims = used_delimiters(message, possible_delimiters);
+        // Without delimiters, the only possible argument is the whole message.
+        let message = if delims.is_empty() { message.trim() } else { message };
+
+        Tokens {
+            message,
+            stream: Stream::new(message),
+            delims,
+            quoted: false,
+        }
+    }
+
+    /// Remove surrounding quotation marks from all of the arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serenity::framework::standard::{Delimiter, Tokens};
+    ///
+    /// let delimiters = [Delimiter::Single(' ')];
+    /// let tokens = Tokens::new("Saw \"The Mist\" \"A Quiet Place\"", &delimiters).quoted();
+    ///
+    /// assert_eq!(tokens.collect::<Vec<_>>(), ["Saw", "The Mist", "A Quiet Place"]);
+    /// ```
+    #[must_use]
+    pub fn quoted(mut self) -> Self {
+        self.quoted = true;
+        self
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (start, end) = if self.delims.is_empty() {
+                if self.stream.is_empty() {
+                    return None;
+                }
+
+                let start = self.stream.offset();
+                self.stream.set(self.stream.len());
+                (start, self.stream.len())
+            } else {
+                lex(&mut self.stream, &self.delims)?.span
+            };
+
+            // Ignore empty arguments.
+            let s = &self.message[start..end];
+            if !s.is_empty() {
+                return Some(if self.quoted { remove_quotes(s) } else { s });
+            }
+        }
+    }
+}
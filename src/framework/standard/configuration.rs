@@ -1,14 +1,21 @@
+#[cfg(feature = "cache")]
+use std::collections::HashMap;
 use std::collections::HashSet;
+#[cfg(feature = "cache")]
+use std::sync::Arc;
+#[cfg(feature = "cache")]
+use std::time::{Duration, Instant};
 
 use futures::future::BoxFuture;
 
 use super::Delimiter;
 use crate::client::Context;
 use crate::model::channel::Message;
-use crate::model::id::{ChannelId, GuildId, UserId};
+use crate::model::id::{ChannelId, GuildId, RoleId, UserId};
 
 type DynamicPrefixHook =
     for<'fut> fn(&'fut Context, &'fut Message) -> BoxFuture<'fut, Option<String>>;
+type GlobalCheckHook = for<'fut> fn(&'fut Context, &'fut Message) -> BoxFuture<'fut, bool>;
 
 /// A configuration struct for deciding whether the framework should allow optional whitespace
 /// between prefixes, group prefixes and command names.
@@ -63,6 +70,135 @@ impl From<(bool, bool, bool)> for WithWhiteSpace {
     }
 }
 
+/// How the framework folds case when [`Configuration::case_insensitive_prefixes`] or
+/// [`Configuration::case_insensitive_commands`] is enabled.
+///
+/// The chosen mode applies to prefixes, group prefixes and command names alike.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum CaseFolding {
+    /// Only ASCII letters are folded, all other characters are left untouched.
+    Ascii,
+    /// Characters are folded using [`str::to_lowercase`].
+    #[default]
+    Lowercase,
+    /// Characters are lowercased, and a fixed set of characters is folded further, approximating
+    /// Unicode full case folding. This is not a complete implementation of it.
+    ///
+    /// The characters folded further are:
+    /// - `ß` and `ẞ` to `ss`, and `ſ` to `s`;
+    /// - `ŉ` to `ʼn`;
+    /// - the final sigma `ς` to `σ`;
+    /// - the Greek symbols `ϐ`, `ϑ`, `ϕ`, `ϖ`, `ϰ`, `ϱ` and `ϵ` to `β`, `θ`, `φ`, `π`, `κ`, `ρ`
+    ///   and `ε`;
+    /// - the Latin ligatures `ﬀ`, `ﬁ`, `ﬂ`, `ﬃ`, `ﬄ`, `ﬅ` and `ﬆ` to their letters.
+    Unicode,
+}
+
+impl CaseFolding {
+    /// Folds the case of the given string according to this mode.
+    #[must_use]
+    pub fn fold(self, s: &str) -> String {
+        match self {
+            Self::Ascii => s.to_ascii_lowercase(),
+            Self::Lowercase => s.to_lowercase(),
+            Self::Unicode => {
+                let mut folded = String::with_capacity(s.len());
+
+                for c in s.chars() {
+                    match c {
+                        // Sharp s and capital sharp s.
+                        '\u{df}' | '\u{1e9e}' => folded.push_str("ss"),
+                        // Long s.
+                        '\u{17f}' => folded.push('s'),
+                        // N preceded by apostrophe.
+                        '\u{149}' => folded.push_str("\u{2bc}n"),
+                        // Final sigma.
+                        '\u{3c2}' => folded.push('\u{3c3}'),
+                        // Greek symbol variants of beta, theta, phi, pi, kappa, rho and epsilon.
+                        '\u{3d0}' => folded.push('\u{3b2}'),
+                        '\u{3d1}' => folded.push('\u{3b8}'),
+                        '\u{3d5}' => folded.push('\u{3c6}'),
+                        '\u{3d6}' => folded.push('\u{3c0}'),
+                        '\u{3f0}' => folded.push('\u{3ba}'),
+                        '\u{3f1}' => folded.push('\u{3c1}'),
+                        '\u{3f5}' => folded.push('\u{3b5}'),
+                        // Latin ligatures.
+                        '\u{fb00}' => folded.push_str("ff"),
+                        '\u{fb01}' => folded.push_str("fi"),
+                        '\u{fb02}' => folded.push_str("fl"),
+                        '\u{fb03}' => folded.push_str("ffi"),
+                        '\u{fb04}' => folded.push_str("ffl"),
+                        '\u{fb05}' | '\u{fb06}' => folded.push_str("st"),
+                        c => folded.extend(c.to_lowercase()),
+                    }
+                }
+
+                folded
+            },
+        }
+    }
+}
+
+/// A cache of the prefixes returned by [`Configuration::dynamic_prefix`] hooks, keyed by guild.
+///
+/// Within its time-to-live, a cached result is reused instead of running the hooks again, such
+/// as to avoid a database query for every message. Messages outside of guilds are never cached.
+///
+/// The cache is cheap to clone, and clones share their entries, so a clone can be kept around,
+/// for instance in [`Context::data`], to [`Self::invalidate`] a guild once it changes its prefix.
+///
+/// [`Context::data`]: crate::client::Context::data
+#[cfg(feature = "cache")]
+#[derive(Clone, Debug)]
+pub struct PrefixCache {
+    ttl: Duration,
+    entries: Arc<parking_lot::Mutex<HashMap<GuildId, CachedPrefixes>>>,
+}
+
+/// The prefixes cached for a guild, along with when they were computed.
+#[cfg(feature = "cache")]
+type CachedPrefixes = (Vec<String>, Instant);
+
+#[cfg(feature = "cache")]
+impl PrefixCache {
+    /// Creates an empty cache whose entries are reused for `ttl` after being computed.
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Arc::default(),
+        }
+    }
+
+    /// Forgets the prefixes cached for the given guild, so that they are computed again on the
+    /// next message sent in it.
+    pub fn invalidate(&self, guild_id: GuildId) {
+        self.entries.lock().remove(&guild_id);
+    }
+
+    /// Forgets the prefixes cached for all guilds.
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+
+    pub(crate) fn get(&self, guild_id: GuildId) -> Option<Vec<String>> {
+        let mut entries = self.entries.lock();
+        let (prefixes, cached_at) = entries.get(&guild_id)?;
+
+        if cached_at.elapsed() < self.ttl {
+            return Some(prefixes.clone());
+        }
+
+        entries.remove(&guild_id);
+        None
+    }
+
+    pub(crate) fn insert(&self, guild_id: GuildId, prefixes: Vec<String>) {
+        self.entries.lock().insert(guild_id, (prefixes, Instant::now()));
+    }
+}
+
 /// The configuration to use for a [`StandardFramework`] associated with a [`Client`] instance.
 ///
 /// This allows setting configurations like the depth to search for commands, whether to treat
@@ -108,8 +244,18 @@ pub struct Configuration {
     #[doc(hidden)]
     pub with_whitespace: WithWhiteSpace,
     #[doc(hidden)]
+    pub require_prefix_touches_command: bool,
+    #[doc(hidden)]
     pub by_space: bool,
     #[doc(hidden)]
+    pub quoted_commands: bool,
+    #[doc(hidden)]
+    pub help_takes_precedence: bool,
+    #[doc(hidden)]
+    pub max_command_depth: usize,
+    #[doc(hidden)]
+    pub whitespace: fn(char) -> bool,
+    #[doc(hidden)]
     pub blocked_guilds: HashSet<GuildId>,
     #[doc(hidden)]
     pub blocked_users: HashSet<UserId>,
@@ -119,22 +265,39 @@ pub struct Configuration {
     pub disabled_commands: HashSet<String>,
     #[doc(hidden)]
     pub dynamic_prefixes: Vec<DynamicPrefixHook>,
+    #[cfg(feature = "cache")]
+    #[doc(hidden)]
+    pub prefix_cache: Option<PrefixCache>,
+    #[doc(hidden)]
+    pub global_check: Option<GlobalCheckHook>,
     #[doc(hidden)]
     pub ignore_bots: bool,
     #[doc(hidden)]
     pub ignore_webhooks: bool,
     #[doc(hidden)]
-    pub on_mention: Option<String>,
+    pub ignore_timed_out_members: bool,
+    #[doc(hidden)]
+    pub on_mention: HashSet<String>,
+    #[doc(hidden)]
+    pub on_role_mention: Option<String>,
     #[doc(hidden)]
     pub owners: HashSet<UserId>,
     #[doc(hidden)]
     pub prefixes: Vec<String>,
     #[doc(hidden)]
+    pub suffixes: Vec<String>,
+    #[doc(hidden)]
+    pub leading_skip: Option<fn(&str) -> usize>,
+    #[doc(hidden)]
     pub no_dm_prefix: bool,
     #[doc(hidden)]
     pub delimiters: Vec<Delimiter>,
     #[doc(hidden)]
-    pub case_insensitive: bool,
+    pub case_insensitive_prefixes: bool,
+    #[doc(hidden)]
+    pub case_insensitive_commands: bool,
+    #[doc(hidden)]
+    pub case_folding: CaseFolding,
 }
 
 impl Configuration {
@@ -175,6 +338,19 @@ impl Configuration {
         self
     }
 
+    /// Whether the prefix must be directly followed by the command, rejecting messages such as
+    /// `! ping` where whitespace separates the two.
+    ///
+    /// Messages rejected this way are treated as if they had no prefix. This applies regardless of
+    /// [`Self::with_whitespace`], and does not affect mentions used as a prefix.
+    ///
+    /// **Note**: Defaults to `false`.
+    pub fn require_prefix_touches_command(&mut self, b: bool) -> &mut Self {
+        self.require_prefix_touches_command = b;
+
+        self
+    }
+
     /// Whether the framework should split the message by a space first to parse the group or
     /// command. If set to false, it will only test part of the message by the *length* of the
     /// group's or command's names.
@@ -186,6 +362,67 @@ impl Configuration {
         self
     }
 
+    /// Whether a group or command name in double quotes should be read as a single name, allowing
+    /// names that contain whitespace to be invoked with [`Self::by_space`] enabled.
+    ///
+    /// For example, `~"say hello" world` invokes the command named `say hello`, with `world` as
+    /// its arguments.
+    ///
+    /// **Note**: Defaults to `false`
+    pub fn quoted_commands(&mut self, b: bool) -> &mut Self {
+        self.quoted_commands = b;
+
+        self
+    }
+
+    /// Whether the names of the help command take precedence over commands of the same name.
+    ///
+    /// When disabled, the help command is only invoked if no other command matches, allowing a
+    /// regular command named `help` to replace it.
+    ///
+    /// **Note**: Defaults to `true`
+    pub fn help_takes_precedence(&mut self, b: bool) -> &mut Self {
+        self.help_takes_precedence = b;
+
+        self
+    }
+
+    /// How deep sub-commands and sub-groups are parsed, where `0` only allows top-level commands
+    /// and groups.
+    ///
+    /// A message reaching past this depth is treated as an unrecognised command, rather than
+    /// parsing further.
+    ///
+    /// **Note**: Defaults to `16`
+    pub fn max_command_depth(&mut self, depth: usize) -> &mut Self {
+        self.max_command_depth = depth;
+
+        self
+    }
+
+    /// Sets which characters are treated as whitespace separating the prefix, group prefixes and
+    /// command names from each other.
+    ///
+    /// This does not affect how arguments are split, which is controlled by [`Self::delimiters`].
+    ///
+    /// **Note**: Defaults to [`char::is_whitespace`].
+    ///
+    /// # Examples
+    ///
+    /// Accept both `~ping,pong` and `~ping pong`:
+    ///
+    /// ```rust,no_run
+    /// use serenity::framework::StandardFramework;
+    ///
+    /// let framework = StandardFramework::new();
+    /// framework.configure(|c| c.whitespace(|c| c == ',' || c == ' '));
+    /// ```
+    pub fn whitespace(&mut self, f: fn(char) -> bool) -> &mut Self {
+        self.whitespace = f;
+
+        self
+    }
+
     /// HashSet of channels Ids where commands will be working.
     ///
     /// **Note**: Defaults to an empty HashSet.
@@ -260,8 +497,69 @@ impl Configuration {
         self
     }
 
+    /// Adds a guild to the [blocked guilds], such as from a moderation command while the bot is
+    /// running.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use serenity::framework::StandardFramework;
+    /// use serenity::model::id::GuildId;
+    ///
+    /// let framework = StandardFramework::new();
+    /// framework.configure(|c| c.block_guild(GuildId::new(7)));
+    /// ```
+    ///
+    /// [blocked guilds]: Self::blocked_guilds
+    pub fn block_guild(&mut self, guild_id: GuildId) -> &mut Self {
+        self.blocked_guilds.insert(guild_id);
+
+        self
+    }
+
+    /// Removes a guild from the [blocked guilds].
+    ///
+    /// [blocked guilds]: Self::blocked_guilds
+    pub fn unblock_guild(&mut self, guild_id: GuildId) -> &mut Self {
+        self.blocked_guilds.remove(&guild_id);
+
+        self
+    }
+
+    /// Adds a user to the [blocked users], such as from a moderation command while the bot is
+    /// running.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use serenity::framework::StandardFramework;
+    /// use serenity::model::id::UserId;
+    ///
+    /// let framework = StandardFramework::new();
+    /// framework.configure(|c| c.block_user(UserId::new(7)));
+    /// ```
+    ///
+    /// [blocked users]: Self::blocked_users
+    pub fn block_user(&mut self, user_id: UserId) -> &mut Self {
+        self.blocked_users.insert(user_id);
+
+        self
+    }
+
+    /// Removes a user from the [blocked users].
+    ///
+    /// [blocked users]: Self::blocked_users
+    pub fn unblock_user(&mut self, user_id: UserId) -> &mut Self {
+        self.blocked_users.remove(&user_id);
+
+        self
+    }
+
     /// HashSet of command names that won't be run.
     ///
+    /// If [`Self::case_insensitive_commands`] is enabled, the names are compared
+    /// case-insensitively.
+    ///
     /// **Note**: Defaults to an empty HashSet.
     ///
     /// # Examples
@@ -358,11 +656,45 @@ impl Configuration {
         self
     }
 
+    /// Reuses the prefixes returned by the [`Self::dynamic_prefix`] hooks for messages in the
+    /// same guild, until the cache's time-to-live runs out or the guild is invalidated.
+    ///
+    /// **Note**: Defaults to no caching, running the hooks for every message.
+    ///
+    /// # Examples
+    ///
+    /// Cache prefixes for five minutes, keeping a handle around to invalidate a guild when its
+    /// prefix is changed:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// use serenity::framework::standard::PrefixCache;
+    /// use serenity::framework::StandardFramework;
+    ///
+    /// let prefixes = PrefixCache::new(Duration::from_secs(300));
+    ///
+    /// let framework = StandardFramework::new();
+    /// framework.configure(|c| {
+    ///     c.dynamic_prefix(|_, _| Box::pin(async move { Some("!".to_string()) }))
+    ///         .prefix_cache(prefixes.clone())
+    /// });
+    /// ```
+    #[cfg(feature = "cache")]
+    pub fn prefix_cache(&mut self, cache: PrefixCache) -> &mut Self {
+        self.prefix_cache = Some(cache);
+
+        self
+    }
+
     /// Whether the bot should respond to other bots.
     ///
     /// For example, if this is set to false, then the bot will respond to any other bots including
     /// itself.
     ///
+    /// Messages sent by webhooks are not affected by this, see [`Self::ignore_webhooks`].
+    ///
     /// **Note**: Defaults to `true`.
     pub fn ignore_bots(&mut self, ignore_bots: bool) -> &mut Self {
         self.ignore_bots = ignore_bots;
@@ -372,6 +704,9 @@ impl Configuration {
 
     /// If set to true, bot will ignore all commands called by webhooks.
     ///
+    /// This is independent of [`Self::ignore_bots`], so webhook messages can be handled while
+    /// still ignoring other bots.
+    ///
     /// **Note**: Defaults to `true`.
     pub fn ignore_webhooks(&mut self, ignore_webhooks: bool) -> &mut Self {
         self.ignore_webhooks = ignore_webhooks;
@@ -379,6 +714,51 @@ impl Configuration {
         self
     }
 
+    /// A check run for every message with a prefix, before the command is resolved or any of its
+    /// own checks are run. Returning `false` blocks the invocation with
+    /// [`DispatchError::GloballyBlocked`], such as while the bot is in maintenance mode.
+    ///
+    /// **Note**: Defaults to no global check.
+    ///
+    /// # Examples
+    ///
+    /// Block all commands while a flag is set:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::prelude::*;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// use serenity::framework::StandardFramework;
+    ///
+    /// static MAINTENANCE: AtomicBool = AtomicBool::new(false);
+    ///
+    /// let framework = StandardFramework::new();
+    /// framework.configure(|c| {
+    ///     c.global_check(|_, _| Box::pin(async { !MAINTENANCE.load(Ordering::Relaxed) }))
+    /// });
+    /// ```
+    ///
+    /// [`DispatchError::GloballyBlocked`]: super::DispatchError::GloballyBlocked
+    pub fn global_check(&mut self, global_check: GlobalCheckHook) -> &mut Self {
+        self.global_check = Some(global_check);
+
+        self
+    }
+
+    /// If set to true, members that are timed out in a guild can't invoke commands there, which
+    /// fails with [`DispatchError::UserTimedOut`].
+    ///
+    /// This requires the member to be cached, like the permission and role checks.
+    ///
+    /// **Note**: Defaults to `false`.
+    ///
+    /// [`DispatchError::UserTimedOut`]: super::DispatchError::UserTimedOut
+    pub fn ignore_timed_out_members(&mut self, ignore_timed_out_members: bool) -> &mut Self {
+        self.ignore_timed_out_members = ignore_timed_out_members;
+
+        self
+    }
+
     /// Whether or not to respond to commands initiated with `id_to_mention`.
     ///
     /// **Note**: that this can be used in conjunction with [`Self::prefix`].
@@ -397,8 +777,51 @@ impl Configuration {
     /// The former is a direct mention, while the latter is a nickname mention, which aids mobile
     /// devices in determining whether to display a user's nickname. It has no real meaning for
     /// your bot, and the library encourages you to ignore differentiating between the two.
+    ///
+    /// To respond to mentions of multiple IDs, use [`Self::on_mentions`].
     pub fn on_mention(&mut self, id_to_mention: Option<UserId>) -> &mut Self {
-        self.on_mention = id_to_mention.map(|id| id.to_string());
+        self.on_mentions(id_to_mention)
+    }
+
+    /// Whether or not to respond to commands initiated with a mention of any of `ids_to_mention`,
+    /// such as a bot deployed under different application IDs.
+    ///
+    /// This replaces any ID set with [`Self::on_mention`].
+    ///
+    /// **Note**: Defaults to ignore mentions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use serenity::framework::StandardFramework;
+    /// use serenity::model::id::UserId;
+    ///
+    /// let framework = StandardFramework::new();
+    /// framework.configure(|c| c.on_mentions([UserId::new(7), UserId::new(77)]));
+    /// ```
+    pub fn on_mentions(&mut self, ids_to_mention: impl IntoIterator<Item = UserId>) -> &mut Self {
+        self.on_mention = ids_to_mention.into_iter().map(|id| id.to_string()).collect();
+
+        self
+    }
+
+    /// Whether or not to respond to commands initiated with a mention of the role `id_to_mention`,
+    /// such as a bot's managed role.
+    ///
+    /// **Note**: that this can be used in conjunction with [`Self::prefix`] and
+    /// [`Self::on_mention`].
+    ///
+    /// **Note**: Defaults to ignore role mentions.
+    ///
+    /// # Examples
+    ///
+    /// Setting this to an ID will allow the following type of mention to be responded to:
+    ///
+    /// ```ignore
+    /// <@&245571012924538880> about
+    /// ```
+    pub fn on_role_mention(&mut self, id_to_mention: Option<RoleId>) -> &mut Self {
+        self.on_role_mention = id_to_mention.map(|id| id.to_string());
 
         self
     }
@@ -491,13 +914,72 @@ impl Configuration {
         self
     }
 
+    /// Sets the suffixes a message must end with for a command to be executed. Each can be a
+    /// string slice of any non-zero length.
+    ///
+    /// When set, the suffix is stripped off before the arguments are parsed, along with any
+    /// whitespace preceding it. Messages not ending in one of the suffixes are treated as normal
+    /// messages.
+    ///
+    /// **Note**: Defaults to no suffixes, in which case messages are not required to end in
+    /// anything.
+    ///
+    /// # Examples
+    ///
+    /// Respond to messages like `~ping!`:
+    ///
+    /// ```rust,no_run
+    /// use serenity::framework::StandardFramework;
+    ///
+    /// let framework = StandardFramework::new();
+    /// framework.configure(|c| c.prefix("~").suffixes(vec!["!"]));
+    /// ```
+    #[inline]
+    pub fn suffixes<T: ToString>(&mut self, suffixes: impl IntoIterator<Item = T>) -> &mut Self {
+        self.suffixes =
+            suffixes.into_iter().map(|s| s.to_string()).filter(|s| !s.is_empty()).collect();
+
+        self
+    }
+
+    /// Sets a function returning how many bytes to skip at the start of a message, before
+    /// looking for a mention or prefix. This is useful when commands are embedded in a larger
+    /// format, such as after a fixed header.
+    ///
+    /// Messages for which the returned number of bytes is past the end of the message or inside
+    /// a character are treated as normal messages.
+    ///
+    /// **Note**: Defaults to not skipping anything.
+    ///
+    /// # Examples
+    ///
+    /// Respond to messages like `[relay] ~ping`, skipping the bracketed tag:
+    ///
+    /// ```rust,no_run
+    /// use serenity::framework::StandardFramework;
+    ///
+    /// let framework = StandardFramework::new();
+    /// framework.configure(|c| {
+    ///     c.leading_skip(|content| match content.find("] ") {
+    ///         Some(end) if content.starts_with('[') => end + 2,
+    ///         _ => 0,
+    ///     })
+    /// });
+    /// ```
+    pub fn leading_skip(&mut self, leading_skip: fn(&str) -> usize) -> &mut Self {
+        self.leading_skip = Some(leading_skip);
+
+        self
+    }
+
     /// Sets whether command execution can be done without a prefix. Works only in private channels.
     ///
-    /// **Note**: Defaults to `false`.
+    /// Prefixes are still accepted in private channels when this is enabled, and are required
+    /// everywhere else.
     ///
-    /// # Note
+    /// **Note**: Defaults to `false`.
     ///
-    /// The `cache` feature is required. If disabled this does absolutely nothing.
+    /// **Note**: Has no effect if [`Self::allow_dm`] is set to `false`.
     #[inline]
     pub fn no_dm_prefix(&mut self, b: bool) -> &mut Self {
         self.no_dm_prefix = b;
@@ -555,15 +1037,65 @@ impl Configuration {
     /// `~Command`, or `~COMMAND`; `mayacommand`, `MayACommand`, `MAYACOMMAND`, et cetera.
     ///
     /// Setting this to `true` will result in *all* prefixes and command names to be case
-    /// insensitive.
+    /// insensitive. This is a shorthand for setting both [`Self::case_insensitive_prefixes`] and
+    /// [`Self::case_insensitive_commands`].
     ///
     /// **Note**: Defaults to `false`.
     pub fn case_insensitivity(&mut self, cs: bool) -> &mut Self {
-        self.case_insensitive = cs;
+        self.case_insensitive_prefixes(cs).case_insensitive_commands(cs)
+    }
 
-        for prefix in &mut self.prefixes {
-            *prefix = prefix.to_lowercase();
-        }
+    /// Whether prefixes and suffixes are matched case-insensitively, such as `~` and `Bot` from
+    /// [`Self::prefixes`] and [`Self::dynamic_prefix`].
+    ///
+    /// Mentions are unaffected, as they don't have a case.
+    ///
+    /// **Note**: Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// Only accept the prefix `Bot` as written, while accepting `Bot ping` and `Bot PING` alike:
+    ///
+    /// ```rust,no_run
+    /// use serenity::framework::StandardFramework;
+    ///
+    /// let framework = StandardFramework::new();
+    /// framework.configure(|c| c.prefix("Bot").case_insensitive_commands(true));
+    /// ```
+    pub fn case_insensitive_prefixes(&mut self, cs: bool) -> &mut Self {
+        self.case_insensitive_prefixes = cs;
+
+        self
+    }
+
+    /// Whether command names, group prefixes, help names and [`Self::disabled_commands`] are
+    /// matched case-insensitively.
+    ///
+    /// **Note**: Defaults to `false`.
+    pub fn case_insensitive_commands(&mut self, cs: bool) -> &mut Self {
+        self.case_insensitive_commands = cs;
+
+        self
+    }
+
+    /// Sets how case is folded when [`Self::case_insensitive_prefixes`] or
+    /// [`Self::case_insensitive_commands`] is enabled.
+    ///
+    /// **Note**: Defaults to [`CaseFolding::Lowercase`].
+    ///
+    /// # Examples
+    ///
+    /// Treat `~STRASSE` and `~straße` as the same command:
+    ///
+    /// ```rust,no_run
+    /// use serenity::framework::standard::CaseFolding;
+    /// use serenity::framework::StandardFramework;
+    ///
+    /// let framework = StandardFramework::new();
+    /// framework.configure(|c| c.case_folding(CaseFolding::Unicode).case_insensitivity(true));
+    /// ```
+    pub fn case_folding(&mut self, folding: CaseFolding) -> &mut Self {
+        self.case_folding = folding;
 
         self
     }
@@ -574,38 +1106,86 @@ impl Default for Configuration {
     ///
     /// - **allow_dm** to `true`
     /// - **with_whitespace** to `(false, true, true)`
+    /// - **require_prefix_touches_command** to `false`
     /// - **by_space** to `true`
+    /// - **quoted_commands** to `false`
+    /// - **help_takes_precedence** to `true`
+    /// - **max_command_depth** to `16`
+    /// - **whitespace** to [`char::is_whitespace`]
     /// - **blocked_guilds** to an empty HashSet
     /// - **blocked_users** to an empty HashSet,
     /// - **allowed_channels** to an empty HashSet,
-    /// - **case_insensitive** to `false`
+    /// - **case_insensitive_prefixes** to `false`
+    /// - **case_insensitive_commands** to `false`
+    /// - **case_folding** to [`CaseFolding::Lowercase`]
     /// - **delimiters** to `vec![' ']`
     /// - **disabled_commands** to an empty HashSet
     /// - **dynamic_prefixes** to an empty vector
+    /// - **prefix_cache** to [`None`]
+    /// - **global_check** to [`None`]
     /// - **ignore_bots** to `true`
     /// - **ignore_webhooks** to `true`
+    /// - **ignore_timed_out_members** to `false`
     /// - **no_dm_prefix** to `false`
-    /// - **on_mention** to `false`
+    /// - **on_mention** to an empty HashSet
+    /// - **on_role_mention** to [`None`]
     /// - **owners** to an empty HashSet
     /// - **prefix** to "~"
+    /// - **suffixes** to an empty vector
+    /// - **leading_skip** to [`None`]
     fn default() -> Configuration {
         Configuration {
             allow_dm: true,
             with_whitespace: WithWhiteSpace::default(),
+            require_prefix_touches_command: false,
             by_space: true,
+            quoted_commands: false,
+            help_takes_precedence: true,
+            max_command_depth: 16,
+            whitespace: char::is_whitespace,
             blocked_guilds: HashSet::default(),
             blocked_users: HashSet::default(),
             allowed_channels: HashSet::default(),
-            case_insensitive: false,
+            case_insensitive_prefixes: false,
+            case_insensitive_commands: false,
+            case_folding: CaseFolding::Lowercase,
             delimiters: vec![Delimiter::Single(' ')],
             disabled_commands: HashSet::default(),
             dynamic_prefixes: Vec::new(),
+            #[cfg(feature = "cache")]
+            prefix_cache: None,
+            global_check: None,
             ignore_bots: true,
             ignore_webhooks: true,
+            ignore_timed_out_members: false,
             no_dm_prefix: false,
-            on_mention: None,
+            on_mention: HashSet::default(),
+            on_role_mention: None,
             owners: HashSet::default(),
             prefixes: vec![String::from("~")],
+            suffixes: Vec::new(),
+            leading_skip: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::CaseFolding;
+
+    #[test]
+    fn case_folding_sharp_s() {
+        assert_eq!(CaseFolding::Ascii.fold("STRA\u{df}E"), "stra\u{df}e");
+        assert_eq!(CaseFolding::Lowercase.fold("STRA\u{df}E"), "stra\u{df}e");
+        assert_eq!(CaseFolding::Unicode.fold("STRA\u{df}E"), "strasse");
+        assert_eq!(CaseFolding::Unicode.fold("STRA\u{df}E"), CaseFolding::Unicode.fold("STRASSE"));
+    }
+
+    #[test]
+    fn case_folding_dotted_capital_i() {
+        assert_eq!(CaseFolding::Ascii.fold("\u{130}"), "\u{130}");
+        assert_eq!(CaseFolding::Lowercase.fold("\u{130}"), "i\u{307}");
+        assert_eq!(CaseFolding::Unicode.fold("\u{130}"), "i\u{307}");
+        assert_eq!(CaseFolding::Ascii.fold("I"), "i");
+    }
+}
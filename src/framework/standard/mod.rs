@@ -6,17 +6,22 @@ pub mod macros {
 mod args;
 mod configuration;
 mod parse;
+mod registry;
 mod structures;
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
-pub use args::{Args, Delimiter, Error as ArgError, Iter, RawArguments};
+pub use args::{Args, Delimiter, Error as ArgError, Iter, RawArguments, Tokens};
 use async_trait::async_trait;
-pub use configuration::{Configuration, WithWhiteSpace};
+#[cfg(feature = "cache")]
+pub use configuration::PrefixCache;
+pub use configuration::{CaseFolding, Configuration, WithWhiteSpace};
 use futures::future::BoxFuture;
 use parse::map::{CommandMap, GroupMap, Map};
+pub use parse::{check_command_access, resolve_help_target, AccessContext, HelpTarget};
 use parse::{Invoke, ParseError};
+pub use registry::{CommandView, GroupView};
 pub use structures::buckets::BucketBuilder;
 use structures::buckets::{Bucket, RateLimitAction};
 pub use structures::*;
@@ -46,15 +51,25 @@ pub enum DispatchError {
     /// When a custom function check has failed.
     CheckFailed(&'static str, Reason),
     /// When the command caller has exceeded a ratelimit bucket.
+    ///
+    /// The [`RateLimitInfo`] carries the time remaining until the bucket allows another
+    /// invocation, as well as what the bucket collects tickets for.
     Ratelimited(RateLimitInfo),
     /// When the requested command is disabled in bot configuration.
-    CommandDisabled,
+    ///
+    /// Carries the name the command was invoked by.
+    CommandDisabled(String),
     /// When the user is blocked in bot configuration.
     BlockedUser,
+    /// When the user is timed out in the guild and
+    /// [`Configuration::ignore_timed_out_members`] is enabled.
+    UserTimedOut,
     /// When the guild or its owner is blocked in bot configuration.
     BlockedGuild,
     /// When the channel blocked in bot configuration.
     BlockedChannel,
+    /// When [`Configuration::global_check`] has failed.
+    GloballyBlocked,
     /// When the requested command can only be used in a direct message or group
     /// channel.
     OnlyForDM,
@@ -67,15 +82,77 @@ pub enum DispatchError {
     LackingRole,
     /// When the command requester lacks specific required permissions.
     LackingPermissions(Permissions),
+    /// When the bot itself lacks the permissions required to perform the command.
+    BotLackingPermissions(Permissions),
+    /// When a command requiring permissions is invoked in a direct message, where there are no
+    /// guild permissions to check against.
+    PermissionsUnavailableInDM(Permissions),
     /// When there are too few arguments.
     NotEnoughArguments { min: u16, given: usize },
     /// When there are too many arguments.
     TooManyArguments { max: u16, given: usize },
 }
 
+impl DispatchError {
+    /// Returns the broad category of this error, for instance to group errors in metrics without
+    /// matching on every variant.
+    #[must_use]
+    pub fn category(&self) -> DispatchErrorKind {
+        match self {
+            Self::LackingRole | Self::LackingPermissions(_) | Self::BotLackingPermissions(_) => {
+                DispatchErrorKind::Permissions
+            },
+            Self::OnlyForDM | Self::OnlyForGuilds | Self::PermissionsUnavailableInDM(_) => {
+                DispatchErrorKind::Scope
+            },
+            Self::OnlyForOwners => DispatchErrorKind::Ownership,
+            Self::Ratelimited(_) => DispatchErrorKind::Ratelimit,
+            Self::CommandDisabled(_)
+            | Self::BlockedUser
+            | Self::UserTimedOut
+            | Self::BlockedGuild
+            | Self::BlockedChannel
+            | Self::GloballyBlocked => DispatchErrorKind::Disabled,
+            Self::CheckFailed(..)
+            | Self::NotEnoughArguments {
+                ..
+            }
+            | Self::TooManyArguments {
+                ..
+            } => DispatchErrorKind::Other,
+        }
+    }
+}
+
+/// The category of a [`DispatchError`], as returned by [`DispatchError::category`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum DispatchErrorKind {
+    /// The command caller lacks required permissions or roles, or the bot lacks the permissions
+    /// to perform the command.
+    Permissions,
+    /// The command can't be used in the kind of channel it was invoked in.
+    Scope,
+    /// The command can only be used by bot owners.
+    Ownership,
+    /// The command caller has exceeded a ratelimit bucket.
+    Ratelimit,
+    /// The command, or the user, guild or channel it was invoked by or in, is disabled or blocked
+    /// in bot configuration.
+    Disabled,
+    /// Any other failure, such as a failed check or a wrong number of arguments.
+    Other,
+}
+
 type DispatchHook =
     for<'fut> fn(&'fut Context, &'fut Message, DispatchError, &'fut str) -> BoxFuture<'fut, ()>;
 type BeforeHook = for<'fut> fn(&'fut Context, &'fut Message, &'fut str) -> BoxFuture<'fut, bool>;
+type BeforeCommandHook = for<'fut> fn(
+    &'fut Context,
+    &'fut Message,
+    &'fut CommandGroup,
+    &'fut Command,
+) -> BoxFuture<'fut, bool>;
 type AfterHook = for<'fut> fn(
     &'fut Context,
     &'fut Message,
@@ -84,6 +161,8 @@ type AfterHook = for<'fut> fn(
 ) -> BoxFuture<'fut, ()>;
 type UnrecognisedHook =
     for<'fut> fn(&'fut Context, &'fut Message, &'fut str) -> BoxFuture<'fut, ()>;
+type UndispatchedHook =
+    for<'fut> fn(&'fut Context, &'fut Message, &'fut str, Args) -> BoxFuture<'fut, ()>;
 type NormalMessageHook = for<'fut> fn(&'fut Context, &'fut Message) -> BoxFuture<'fut, ()>;
 type PrefixOnlyHook = for<'fut> fn(&'fut Context, &'fut Message) -> BoxFuture<'fut, ()>;
 
@@ -97,13 +176,16 @@ pub struct StandardFramework {
     groups: Vec<(&'static CommandGroup, Map)>,
     buckets: Mutex<HashMap<String, Bucket>>,
     before: Option<BeforeHook>,
+    before_command: Option<BeforeCommandHook>,
     after: Option<AfterHook>,
     dispatch: Option<DispatchHook>,
     unrecognised_command: Option<UnrecognisedHook>,
+    undispatched_command: Option<UndispatchedHook>,
     normal_message: Option<NormalMessageHook>,
     prefix_only: Option<PrefixOnlyHook>,
     config: parking_lot::RwLock<Configuration>,
     help: Option<&'static HelpCommand>,
+    group_helps: Vec<(&'static CommandGroup, &'static HelpCommand)>,
     /// Whether the framework has been "initialized".
     ///
     /// The framework is initialized once one of the following occurs:
@@ -203,11 +285,53 @@ impl StandardFramework {
     }
 
     /// Whether the message should be ignored because it is from a bot or webhook.
+    ///
+    /// Webhook authors are flagged as bots, so webhook messages are only subject to
+    /// [`Configuration::ignore_webhooks`].
     fn should_ignore(&self, msg: &Message) -> bool {
         let config = self.config.read();
 
-        (config.ignore_bots && msg.author.bot)
-            || (config.ignore_webhooks && msg.webhook_id.is_some())
+        if msg.webhook_id.is_some() {
+            config.ignore_webhooks
+        } else {
+            config.ignore_bots && msg.author.bot
+        }
+    }
+
+    /// Returns the error to reject the message with if its author or guild is blocked, or if its
+    /// guild is owned by a blocked user.
+    ///
+    /// Owners are not exempt here; callers decide whether they are.
+    #[cfg_attr(not(feature = "cache"), allow(unused_variables))]
+    fn blocked(config: &Configuration, ctx: &Context, msg: &Message) -> Option<DispatchError> {
+        if config.blocked_users.contains(&msg.author.id) {
+            return Some(DispatchError::BlockedUser);
+        }
+
+        #[cfg(feature = "cache")]
+        let guild_id = msg.guild_id.or_else(|| match msg.channel_id.to_channel_cached(ctx) {
+            Some(Channel::Guild(channel)) => Some(channel.guild_id),
+            _ => None,
+        });
+        #[cfg(not(feature = "cache"))]
+        let guild_id = msg.guild_id;
+
+        if let Some(guild_id) = guild_id {
+            if config.blocked_guilds.contains(&guild_id) {
+                return Some(DispatchError::BlockedGuild);
+            }
+
+            #[cfg(feature = "cache")]
+            {
+                if let Some(guild) = ctx.cache.guild(guild_id) {
+                    if config.blocked_users.contains(&guild.owner_id) {
+                        return Some(DispatchError::BlockedGuild);
+                    }
+                }
+            }
+        }
+
+        None
     }
 
     async fn should_fail<'a>(
@@ -244,25 +368,8 @@ impl StandardFramework {
                 return None;
             }
 
-            if config.blocked_users.contains(&msg.author.id) {
-                return Some(DispatchError::BlockedUser);
-            }
-
-            #[cfg(feature = "cache")]
-            {
-                if let Some(Channel::Guild(channel)) = msg.channel_id.to_channel_cached(ctx) {
-                    let guild_id = channel.guild_id;
-
-                    if config.blocked_guilds.contains(&guild_id) {
-                        return Some(DispatchError::BlockedGuild);
-                    }
-
-                    if let Some(guild) = ctx.cache.guild(guild_id) {
-                        if config.blocked_users.contains(&guild.owner_id) {
-                            return Some(DispatchError::BlockedGuild);
-                        }
-                    }
-                }
+            if let Some(error) = Self::blocked(&config, ctx, msg) {
+                return Some(error);
             }
 
             if !config.allowed_channels.is_empty()
@@ -395,6 +502,54 @@ impl StandardFramework {
         self.groups.retain(|&(g, _)| g != group);
     }
 
+    /// Returns views of the registered groups, in the order they were added, through which their
+    /// commands and nested groups can be walked without invoking anything.
+    pub fn registry(&self) -> impl Iterator<Item = GroupView> + '_ {
+        self.groups.iter().map(|&(group, _)| GroupView::new(group))
+    }
+
+    /// Returns the sorted names of the commands starting with `prefix` that can be invoked
+    /// without a group prefix, such as to suggest completions of a partially typed command.
+    ///
+    /// The names are compared case-insensitively if [`Configuration::case_insensitive_commands`]
+    /// was enabled when the groups were added.
+    #[must_use]
+    pub fn commands_starting_with(&self, prefix: &str) -> Vec<&'static str> {
+        let mut names = self
+            .groups
+            .iter()
+            .filter_map(|(_, map)| match map {
+                Map::Prefixless(_, commands) => Some(commands.starts_with(prefix)),
+                Map::WithPrefixes(_) => None,
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Resolves the command named by an already split path of group prefixes and command names,
+    /// such as `["admin", "kick"]` from a slash command, as parsing `admin kick` from a message
+    /// would.
+    ///
+    /// This lets the same groups back both text and slash commands. No checks are run, as there
+    /// is no message to run them against. Returns [`None`] if no command matches or the command
+    /// is disabled.
+    #[must_use]
+    pub fn resolve_command_path(
+        &self,
+        path: &[&str],
+    ) -> Option<(&'static CommandGroup, &'static Command)> {
+        match parse::command_path(&self.groups, path, &self.config.read()) {
+            Ok(Invoke::Command {
+                group,
+                command,
+            }) => Some((group, command)),
+            _ => None,
+        }
+    }
+
     /// Specify the function that's called in case a command wasn't executed for one reason or
     /// another.
     ///
@@ -448,7 +603,12 @@ impl StandardFramework {
         self
     }
 
-    /// Specify the function to be called on messages comprised of only the prefix.
+    /// Specify the function to be called on messages comprised of only the prefix or a mention,
+    /// optionally followed by whitespace, such as to reply with help.
+    ///
+    /// Unlike [`Self::unrecognised_command`] and [`Self::normal_message`], this is not called for
+    /// messages without a prefix, or when the [`Configuration::no_dm_prefix`] option lets a
+    /// direct message go without one.
     #[must_use]
     pub fn prefix_only(mut self, f: PrefixOnlyHook) -> Self {
         self.prefix_only = Some(f);
@@ -508,6 +668,44 @@ impl StandardFramework {
         self
     }
 
+    /// Specify the function to be called prior to every command's execution, receiving the
+    /// resolved command and the group it belongs to. If that function returns true, the command
+    /// will be executed.
+    ///
+    /// This is called after the hook set with [`Self::before`], and is not called for the help
+    /// command.
+    ///
+    /// # Examples
+    ///
+    /// Using [`Self::before_command`] to log command usage along with its group:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::prelude::*;
+    /// # use serenity::model::prelude::*;
+    /// use serenity::framework::standard::macros::hook;
+    /// use serenity::framework::standard::{Command, CommandGroup};
+    /// use serenity::framework::StandardFramework;
+    ///
+    /// #[hook]
+    /// async fn before_command_hook(
+    ///     _: &Context,
+    ///     _: &Message,
+    ///     group: &CommandGroup,
+    ///     command: &Command,
+    /// ) -> bool {
+    ///     println!("Running command {} of group {}", command.options.names[0], group.name);
+    ///     true
+    /// }
+    ///
+    /// let framework = StandardFramework::new().before_command(before_command_hook);
+    /// ```
+    #[must_use]
+    pub fn before_command(mut self, f: BeforeCommandHook) -> Self {
+        self.before_command = Some(f);
+
+        self
+    }
+
     /// Specify the function to be called after every command's execution. Fourth argument exists
     /// if command returned an error which you can handle.
     ///
@@ -572,6 +770,38 @@ impl StandardFramework {
         self
     }
 
+    /// Specify the function to be called if a named command could not be dispatched, whether it
+    /// was not recognised or failed a check.
+    ///
+    /// Besides the name, the function is given the rest of the message from that name onwards,
+    /// such as to suggest the closest command. This is called alongside
+    /// [`Self::unrecognised_command`] and [`Self::on_dispatch_error`].
+    ///
+    /// # Examples
+    ///
+    /// Using [`Self::undispatched_command`]:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::prelude::*;
+    /// # use serenity::model::prelude::*;
+    /// use serenity::framework::standard::macros::hook;
+    /// use serenity::framework::standard::Args;
+    /// use serenity::framework::StandardFramework;
+    ///
+    /// #[hook]
+    /// async fn undispatched_command_hook(_: &Context, msg: &Message, name: &str, args: Args) {
+    ///     println!("A user named {:?} tried to execute {:?}: {}", msg.author.name, name, args.rest());
+    /// }
+    ///
+    /// let framework = StandardFramework::new().undispatched_command(undispatched_command_hook);
+    /// ```
+    #[must_use]
+    pub fn undispatched_command(mut self, f: UndispatchedHook) -> Self {
+        self.undispatched_command = Some(f);
+
+        self
+    }
+
     /// Specify the function to be called if a message contains no command.
     ///
     /// # Examples
@@ -607,6 +837,22 @@ impl StandardFramework {
 
         self
     }
+
+    /// Sets what code should be executed when a user sends `(prefix)(group prefix)help`, scoped to
+    /// the given group.
+    ///
+    /// The help is only invoked after one of the prefixes of the group, and only receives the group
+    /// itself to display. When the prefixes of several groups with their own help are given, such
+    /// as those of a sub-group, the help of the innermost one is invoked.
+    ///
+    /// Setting a help for a group that already has one replaces it.
+    #[must_use]
+    pub fn group_help(mut self, group: &'static CommandGroup, h: &'static HelpCommand) -> Self {
+        self.group_helps.retain(|(g, _)| !std::ptr::eq(*g, group));
+        self.group_helps.push((group, h));
+
+        self
+    }
 }
 
 #[async_trait]
@@ -619,15 +865,27 @@ impl Framework for StandardFramework {
             return;
         }
 
-        let mut stream = Stream::new(&msg.content);
+        let config = self.config.read().clone();
 
-        stream.take_while_char(char::is_whitespace);
+        let content = parse::suffix(&msg.content, &config);
+        let Some(content) = content.and_then(|content| parse::leading_skip(content, &config)) else {
+            if let Some(normal) = &self.normal_message {
+                normal(&mut ctx, &msg).await;
+            }
 
-        let config = self.config.read().clone();
+            return;
+        };
+
+        let mut stream = Stream::new(content);
 
+        stream.take_while_char(config.whitespace);
+
+        let start = stream.offset();
         let prefix = parse::prefix(&ctx, &msg, &mut stream, &config).await;
+        let prefix_len = stream.offset() - start;
 
-        if prefix.is_some() && stream.rest().is_empty() {
+        let prefix_only = stream.rest().chars().all(config.whitespace);
+        if prefix.as_deref().is_some_and(|prefix| !prefix.is_empty()) && prefix_only {
             if let Some(prefix_only) = &self.prefix_only {
                 prefix_only(&mut ctx, &msg).await;
             }
@@ -635,7 +893,7 @@ impl Framework for StandardFramework {
             return;
         }
 
-        if prefix.is_none() && !(config.no_dm_prefix && msg.is_private()) {
+        if prefix.is_none() {
             if let Some(normal) = &self.normal_message {
                 normal(&mut ctx, &msg).await;
             }
@@ -643,6 +901,40 @@ impl Framework for StandardFramework {
             return;
         }
 
+        // Blocks are checked before parsing since they only need the message, but owners may be
+        // exempt per command, which is left to `should_fail` once the command is resolved.
+        let blocked = if config.owners.contains(&msg.author.id) {
+            None
+        } else {
+            Self::blocked(&config, &ctx, &msg)
+        };
+        if let Some(error) = blocked {
+            if let Some(dispatch) = &self.dispatch {
+                // The command is not resolved yet, so the hook is given the word following the
+                // prefix instead.
+                let name = stream.rest().split(config.whitespace).next().unwrap_or_default();
+                dispatch(&mut ctx, &msg, error, name).await;
+            }
+
+            return;
+        }
+
+        if let Some(global_check) = config.global_check {
+            if !global_check(&ctx, &msg).await {
+                if let Some(dispatch) = &self.dispatch {
+                    // The command is not resolved yet, so the hook is given the word following
+                    // the prefix instead.
+                    let name = stream.rest().split(config.whitespace).next().unwrap_or_default();
+                    dispatch(&mut ctx, &msg, DispatchError::GloballyBlocked, name).await;
+                }
+
+                return;
+            }
+        }
+
+        let group_helps =
+            self.group_helps.iter().map(|(g, h)| (*g, h.options.names)).collect::<Vec<_>>();
+
         let invocation = parse::command(
             &ctx,
             &msg,
@@ -650,16 +942,26 @@ impl Framework for StandardFramework {
             &self.groups,
             &config,
             self.help.map(|h| h.options.names),
+            &group_helps,
         )
         .await;
 
         let invoke = match invocation {
             Ok(i) => i,
-            Err(ParseError::UnrecognisedCommand(unreg)) => {
+            Err(ParseError::UnrecognisedCommand {
+                name: unreg,
+                remainder,
+            }) => {
                 if let Some(unreg) = unreg {
                     if let Some(unrecognised_command) = &self.unrecognised_command {
                         unrecognised_command(&mut ctx, &msg, &unreg).await;
                     }
+
+                    if let Some(undispatched) = &self.undispatched_command {
+                        let args =
+                            Args::new(&remainder, &config.delimiters).with_prefix_len(prefix_len);
+                        undispatched(&mut ctx, &msg, &unreg, args).await;
+                    }
                 }
 
                 if let Some(normal) = &self.normal_message {
@@ -671,29 +973,49 @@ impl Framework for StandardFramework {
             Err(ParseError::Dispatch {
                 error,
                 command_name,
+                remainder,
             }) => {
                 if let Some(dispatch) = &self.dispatch {
                     dispatch(&mut ctx, &msg, error, &command_name).await;
                 }
 
+                if let Some(undispatched) = &self.undispatched_command {
+                    let args =
+                        Args::new(&remainder, &config.delimiters).with_prefix_len(prefix_len);
+                    undispatched(&mut ctx, &msg, &command_name, args).await;
+                }
+
                 return;
             },
         };
 
         match invoke {
-            Invoke::Help(name) => {
+            Invoke::Help {
+                name,
+                group,
+            } => {
                 if !config.allow_dm && msg.is_private() {
                     return;
                 }
 
-                let args = Args::new(stream.rest(), &config.delimiters);
+                let args = Args::new(stream.rest(), &config.delimiters).with_prefix_len(prefix_len);
 
-                let groups = self.groups.iter().map(|(g, _)| *g).collect::<Vec<_>>();
+                let (groups, help) = if let Some(group) = group {
+                    // `parse_command` only returns a scoped help invocation for a group that was
+                    // given one.
+                    #[allow(clippy::unwrap_used)]
+                    let (_, help) =
+                        self.group_helps.iter().find(|(g, _)| std::ptr::eq(*g, group)).unwrap();
 
-                // `parse_command` promises to never return a help invocation if
-                // `StandardFramework::help` is `None`.
-                #[allow(clippy::unwrap_used)]
-                let help = self.help.unwrap();
+                    (vec![group], *help)
+                } else {
+                    // `parse_command` promises to never return a help invocation if
+                    // `StandardFramework::help` is `None`.
+                    #[allow(clippy::unwrap_used)]
+                    let help = self.help.unwrap();
+
+                    (self.groups.iter().map(|(g, _)| *g).collect::<Vec<_>>(), help)
+                };
 
                 if let Some(before) = &self.before {
                     if !before(&mut ctx, &msg, name).await {
@@ -736,7 +1058,7 @@ impl Framework for StandardFramework {
                         delims = Cow::Owned(v);
                     }
 
-                    Args::new(stream.rest(), &delims)
+                    Args::new(stream.rest(), &delims).with_prefix_len(prefix_len)
                 };
 
                 if let Some(error) =
@@ -758,6 +1080,12 @@ impl Framework for StandardFramework {
                     }
                 }
 
+                if let Some(before_command) = &self.before_command {
+                    if !before_command(&mut ctx, &msg, group, command).await {
+                        return;
+                    }
+                }
+
                 let res = (command.fun)(&mut ctx, &msg, args).await;
 
                 // Check if the command wants to revert the bucket by giving back a ticket.
@@ -779,6 +1107,7 @@ impl Framework for StandardFramework {
 
 pub trait CommonOptions {
     fn required_permissions(&self) -> &Permissions;
+    fn bot_required_permissions(&self) -> &Permissions;
     fn allowed_roles(&self) -> &'static [&'static str];
     fn checks(&self) -> &'static [&'static Check];
     fn only_in(&self) -> OnlyIn;
@@ -792,6 +1121,10 @@ impl CommonOptions for &GroupOptions {
         &self.required_permissions
     }
 
+    fn bot_required_permissions(&self) -> &Permissions {
+        &self.bot_required_permissions
+    }
+
     fn allowed_roles(&self) -> &'static [&'static str] {
         self.allowed_roles
     }
@@ -822,6 +1155,10 @@ impl CommonOptions for &CommandOptions {
         &self.required_permissions
     }
 
+    fn bot_required_permissions(&self) -> &Permissions {
+        &self.bot_required_permissions
+    }
+
     fn allowed_roles(&self) -> &'static [&'static str] {
         self.allowed_roles
     }
@@ -881,3 +1218,761 @@ pub(crate) fn has_correct_roles(
             .any(|g| member.roles.contains(&g.id))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use futures::future::FutureExt;
+    use tokio::sync::RwLock;
+    use typemap_rev::TypeMap;
+
+    use super::macros::hook;
+    use super::*;
+    use crate::gateway::ShardMessenger;
+    use crate::http::Http;
+    use crate::model::id::{GuildId, ShardId, UserId, WebhookId};
+
+    pub(super) fn context() -> Context {
+        let (tx, _) = futures::channel::mpsc::unbounded();
+
+        Context {
+            data: Arc::new(RwLock::new(TypeMap::new())),
+            shard: ShardMessenger {
+                tx,
+                #[cfg(feature = "collector")]
+                collectors: Arc::default(),
+            },
+            shard_id: ShardId(0),
+            http: Arc::new(Http::new("token")),
+            #[cfg(feature = "cache")]
+            cache: Arc::default(),
+        }
+    }
+
+    #[test]
+    fn dispatch_error_category() {
+        let rate_limit_info = RateLimitInfo {
+            rate_limit: Duration::from_secs(1),
+            active_delays: 0,
+            max_delays: 0,
+            is_first_try: true,
+            action: RateLimitAction::Cancelled,
+            limited_for: buckets::LimitedFor::User,
+        };
+
+        let cases = [
+            (DispatchError::CheckFailed("check", Reason::Unknown), DispatchErrorKind::Other),
+            (DispatchError::Ratelimited(rate_limit_info), DispatchErrorKind::Ratelimit),
+            (DispatchError::CommandDisabled("ping".to_string()), DispatchErrorKind::Disabled),
+            (DispatchError::BlockedUser, DispatchErrorKind::Disabled),
+            (DispatchError::BlockedGuild, DispatchErrorKind::Disabled),
+            (DispatchError::BlockedChannel, DispatchErrorKind::Disabled),
+            (DispatchError::GloballyBlocked, DispatchErrorKind::Disabled),
+            (DispatchError::OnlyForDM, DispatchErrorKind::Scope),
+            (DispatchError::OnlyForGuilds, DispatchErrorKind::Scope),
+            (
+                DispatchError::PermissionsUnavailableInDM(Permissions::ADMINISTRATOR),
+                DispatchErrorKind::Scope,
+            ),
+            (DispatchError::OnlyForOwners, DispatchErrorKind::Ownership),
+            (DispatchError::LackingRole, DispatchErrorKind::Permissions),
+            (
+                DispatchError::LackingPermissions(Permissions::ADMINISTRATOR),
+                DispatchErrorKind::Permissions,
+            ),
+            (
+                DispatchError::BotLackingPermissions(Permissions::KICK_MEMBERS),
+                DispatchErrorKind::Permissions,
+            ),
+            (
+                DispatchError::NotEnoughArguments {
+                    min: 1,
+                    given: 0,
+                },
+                DispatchErrorKind::Other,
+            ),
+            (
+                DispatchError::TooManyArguments {
+                    max: 1,
+                    given: 2,
+                },
+                DispatchErrorKind::Other,
+            ),
+        ];
+
+        for (error, kind) in cases {
+            assert_eq!(error.category(), kind, "{error:?}");
+        }
+    }
+
+    pub(super) fn message(content: &str) -> Message {
+        Message {
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn message_from(content: &str, author_id: u64) -> Message {
+        let mut msg = message(content);
+        msg.author.id = UserId::new(author_id);
+        msg
+    }
+
+    async fn dispatch(framework: &StandardFramework, msg: Message) {
+        framework
+            .dispatch(FullEvent::Message {
+                ctx: context(),
+                new_message: msg,
+            })
+            .await;
+    }
+
+    pub(super) const COMMAND_OPTIONS: CommandOptions = CommandOptions {
+        checks: &[],
+        bucket: None,
+        names: &[],
+        desc: None,
+        delimiters: &[],
+        usage: None,
+        examples: &[],
+        min_args: None,
+        max_args: None,
+        allowed_roles: &[],
+        required_permissions: Permissions::empty(),
+        bot_required_permissions: Permissions::empty(),
+        help_available: true,
+        only_in: OnlyIn::None,
+        owners_only: false,
+        owner_privilege: true,
+        sub_commands: &[],
+    };
+
+    pub(super) const GROUP_OPTIONS: GroupOptions = GroupOptions {
+        prefixes: &[],
+        only_in: OnlyIn::None,
+        owners_only: false,
+        owner_privilege: true,
+        help_available: true,
+        allowed_roles: &[],
+        required_permissions: Permissions::empty(),
+        bot_required_permissions: Permissions::empty(),
+        checks: &[],
+        default_command: None,
+        description: None,
+        summary: None,
+        commands: &[],
+        sub_groups: &[],
+    };
+
+    /// Declares a command running `$fun` with the given options, along with a group holding only
+    /// it.
+    macro_rules! command_group {
+        (
+            $command:ident,
+            $group:ident,
+            $fun:expr,
+            { $($field:ident: $value:expr),* $(,)? }
+            $(, { $($group_field:ident: $group_value:expr),* $(,)? })?
+        ) => {
+            static $command: Command = Command {
+                fun: $fun,
+                options: &CommandOptions {
+                    $($field: $value,)*
+                    ..COMMAND_OPTIONS
+                },
+            };
+
+            static $group: CommandGroup = CommandGroup {
+                name: stringify!($group),
+                options: &GroupOptions {
+                    $($($group_field: $group_value,)*)?
+                    commands: &[&$command],
+                    ..GROUP_OPTIONS
+                },
+            };
+        };
+    }
+
+    /// Like `command_group!`, with a command counting its invocations in `$counter`.
+    macro_rules! counting_command_group {
+        ($counter:ident, $command:ident, $group:ident, $($options:tt)*) => {
+            static $counter: AtomicUsize = AtomicUsize::new(0);
+
+            command_group!($command, $group, {
+                fn count<'fut>(
+                    _: &'fut Context,
+                    _: &'fut Message,
+                    _: Args,
+                ) -> BoxFuture<'fut, CommandResult> {
+                    $counter.fetch_add(1, Ordering::SeqCst);
+
+                    async { Ok(()) }.boxed()
+                }
+
+                count
+            }, $($options)*);
+        };
+    }
+
+    /// Like `command_group!`, with a command recording a value computed from its arguments in
+    /// `$records`.
+    macro_rules! recording_command_group {
+        (
+            $records:ident: $ty:ty = |$args:ident| $record:expr,
+            $command:ident,
+            $group:ident,
+            $($options:tt)*
+        ) => {
+            static $records: std::sync::Mutex<Vec<$ty>> = std::sync::Mutex::new(Vec::new());
+
+            command_group!($command, $group, {
+                fn record<'fut>(
+                    _: &'fut Context,
+                    _: &'fut Message,
+                    $args: Args,
+                ) -> BoxFuture<'fut, CommandResult> {
+                    $records.lock().unwrap().push($record);
+
+                    async { Ok(()) }.boxed()
+                }
+
+                record
+            }, $($options)*);
+        };
+    }
+
+    pub(super) fn ping<'fut>(
+        _: &'fut Context,
+        _: &'fut Message,
+        _: Args,
+    ) -> BoxFuture<'fut, CommandResult> {
+        async { Ok(()) }.boxed()
+    }
+
+    pub(super) static PING_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["ping"],
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    static SAY_HELLO_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["say hello"],
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    pub(super) static GENERAL_GROUP: CommandGroup = CommandGroup {
+        name: "General",
+        options: &GroupOptions {
+            commands: &[&PING_COMMAND, &SAY_HELLO_COMMAND],
+            ..GROUP_OPTIONS
+        },
+    };
+
+    static BEFORE_COMMAND_MATCHED: AtomicBool = AtomicBool::new(false);
+
+    #[hook]
+    async fn before_command_hook(
+        _: &Context,
+        _: &Message,
+        group: &CommandGroup,
+        command: &Command,
+    ) -> bool {
+        let matched = group == &GENERAL_GROUP && std::ptr::eq(command, &PING_COMMAND);
+        BEFORE_COMMAND_MATCHED.store(matched, Ordering::SeqCst);
+
+        true
+    }
+
+    #[tokio::test]
+    async fn before_command_receives_command() {
+        let framework =
+            StandardFramework::new().group(&GENERAL_GROUP).before_command(before_command_hook);
+
+        dispatch(&framework, message("~ping")).await;
+
+        assert!(BEFORE_COMMAND_MATCHED.load(Ordering::SeqCst));
+    }
+
+    static COOLDOWN_RATELIMITED: AtomicUsize = AtomicUsize::new(0);
+
+    counting_command_group!(COOLDOWN_INVOCATIONS, COOLDOWN_COMMAND, COOLDOWN_GROUP, {
+        bucket: Some("cooldown"),
+        names: &["cooldown"],
+    });
+
+    #[hook]
+    async fn cooldown_dispatch_error(_: &Context, _: &Message, error: DispatchError, _: &str) {
+        if matches!(error, DispatchError::Ratelimited(_)) {
+            COOLDOWN_RATELIMITED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn per_user_cooldown() {
+        let framework = StandardFramework::new()
+            .bucket("cooldown", |b| {
+                // Set directly, as the builder only takes whole seconds.
+                b.delay = Duration::from_millis(50);
+                b.limit_for(buckets::LimitedFor::User)
+            })
+            .await
+            .group(&COOLDOWN_GROUP)
+            .on_dispatch_error(cooldown_dispatch_error);
+
+        dispatch(&framework, message("~cooldown")).await;
+        dispatch(&framework, message("~cooldown")).await;
+        dispatch(&framework, message_from("~cooldown", 2)).await;
+
+        assert_eq!(COOLDOWN_INVOCATIONS.load(Ordering::SeqCst), 2);
+        assert_eq!(COOLDOWN_RATELIMITED.load(Ordering::SeqCst), 1);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        dispatch(&framework, message("~cooldown")).await;
+
+        assert_eq!(COOLDOWN_INVOCATIONS.load(Ordering::SeqCst), 3);
+        assert_eq!(COOLDOWN_RATELIMITED.load(Ordering::SeqCst), 1);
+    }
+
+    static REMAINING_RATELIMITS: std::sync::Mutex<Vec<(Duration, buckets::LimitedFor)>> =
+        std::sync::Mutex::new(Vec::new());
+
+    command_group!(REMAINING_COMMAND, REMAINING_GROUP, ping, {
+        bucket: Some("remaining"),
+        names: &["remaining"],
+    });
+
+    #[hook]
+    async fn remaining_dispatch_error(_: &Context, _: &Message, error: DispatchError, _: &str) {
+        if let DispatchError::Ratelimited(info) = error {
+            REMAINING_RATELIMITS.lock().unwrap().push((info.rate_limit, info.limited_for));
+        }
+    }
+
+    #[tokio::test]
+    async fn ratelimit_remaining() {
+        let framework = StandardFramework::new()
+            .bucket("remaining", |b| b.limit_for(buckets::LimitedFor::Channel).delay(10))
+            .await
+            .group(&REMAINING_GROUP)
+            .on_dispatch_error(remaining_dispatch_error);
+
+        dispatch(&framework, message("~remaining")).await;
+        dispatch(&framework, message("~remaining")).await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        dispatch(&framework, message("~remaining")).await;
+
+        let ratelimits = REMAINING_RATELIMITS.lock().unwrap();
+        assert_eq!(ratelimits.len(), 2);
+        assert!(ratelimits[1].0 < ratelimits[0].0);
+        assert!(ratelimits[0].0 <= Duration::from_secs(10));
+        assert!(ratelimits.iter().all(|(_, scope)| *scope == buckets::LimitedFor::Channel));
+    }
+
+    recording_command_group!(
+        SUFFIX_ARGS: String = |args| args.rest().to_string(),
+        SUFFIXED_COMMAND,
+        SUFFIXED_GROUP,
+        { names: &["suffixed"] }
+    );
+
+    #[tokio::test]
+    async fn suffix() {
+        let framework = StandardFramework::new();
+        framework.configure(|c| c.suffixes(vec!["!"]));
+        let framework = framework.group(&SUFFIXED_GROUP);
+
+        dispatch(&framework, message("~suffixed foo bar !")).await;
+        dispatch(&framework, message("~suffixed!")).await;
+        dispatch(&framework, message("~suffixed foo bar")).await;
+
+        assert_eq!(*SUFFIX_ARGS.lock().unwrap(), vec!["foo bar".to_string(), String::new()]);
+    }
+
+    recording_command_group!(
+        PREFIX_LENS: usize = |args| args.prefix_len(),
+        PREFIXED_COMMAND,
+        PREFIXED_GROUP,
+        { names: &["prefixed"] }
+    );
+
+    #[tokio::test]
+    async fn prefix_len() {
+        let framework = StandardFramework::new();
+        framework.configure(|c| {
+            c.prefix("!!").on_mention(Some(UserId::new(1))).with_whitespace((true, true, true))
+        });
+        let framework = framework.group(&PREFIXED_GROUP);
+
+        dispatch(&framework, message("<@1> prefixed")).await;
+        dispatch(&framework, message("!!prefixed")).await;
+        dispatch(&framework, message("!! prefixed foo")).await;
+
+        assert_eq!(*PREFIX_LENS.lock().unwrap(), vec![5, 2, 3]);
+    }
+
+    recording_command_group!(
+        SEPARATED_ARGS: String = |args| args.rest().to_string(),
+        SEPARATED_COMMAND,
+        SEPARATED_GROUP,
+        { names: &["separated"] },
+        { prefixes: &["group"] }
+    );
+
+    #[tokio::test]
+    async fn custom_whitespace() {
+        let framework = StandardFramework::new();
+        framework.configure(|c| c.whitespace(|c| c == ',' || c == ' '));
+        let framework = framework.group(&SEPARATED_GROUP);
+
+        dispatch(&framework, message("~group,separated,foo")).await;
+        dispatch(&framework, message("~group separated foo")).await;
+        dispatch(&framework, message("~group_separated_foo")).await;
+
+        assert_eq!(*SEPARATED_ARGS.lock().unwrap(), vec!["foo".to_string(), "foo".to_string()]);
+    }
+
+    static CHECK_FAILURES: std::sync::Mutex<Vec<&'static str>> = std::sync::Mutex::new(Vec::new());
+
+    #[hook]
+    async fn premium_check(
+        _: &Context,
+        msg: &Message,
+        _: &mut Args,
+        _: &CommandOptions,
+    ) -> Result<(), Reason> {
+        if msg.author.id == UserId::new(1) {
+            Ok(())
+        } else {
+            Err(Reason::User("Not a premium user.".to_string()))
+        }
+    }
+
+    static PREMIUM_CHECK: Check = Check {
+        name: "premium",
+        function: premium_check,
+        check_in_help: true,
+        display_in_help: true,
+    };
+
+    counting_command_group!(CHECKED_INVOCATIONS, CHECKED_COMMAND, CHECKED_GROUP, {
+        checks: &[&PREMIUM_CHECK],
+        names: &["checked"],
+    });
+
+    #[hook]
+    async fn checked_dispatch_error(_: &Context, _: &Message, error: DispatchError, _: &str) {
+        if let DispatchError::CheckFailed(name, Reason::User(_)) = error {
+            CHECK_FAILURES.lock().unwrap().push(name);
+        }
+    }
+
+    counting_command_group!(IGNORE_INVOCATIONS, COUNTED_COMMAND, COUNTED_GROUP, {
+        names: &["counted"],
+    });
+
+    #[tokio::test]
+    async fn ignore_webhooks() {
+        let framework = StandardFramework::new().group(&COUNTED_GROUP);
+        framework.configure(|c| c.ignore_bots(true).ignore_webhooks(false));
+
+        let mut webhook = message("~counted");
+        webhook.author.bot = true;
+        webhook.webhook_id = Some(WebhookId::new(1));
+
+        let mut bot = message("~counted");
+        bot.author.bot = true;
+
+        dispatch(&framework, webhook.clone()).await;
+        dispatch(&framework, bot.clone()).await;
+        assert_eq!(IGNORE_INVOCATIONS.load(Ordering::SeqCst), 1);
+
+        framework.configure(|c| c.ignore_bots(false).ignore_webhooks(true));
+
+        dispatch(&framework, webhook).await;
+        dispatch(&framework, bot).await;
+        assert_eq!(IGNORE_INVOCATIONS.load(Ordering::SeqCst), 2);
+    }
+
+    static UNRECOGNISED_NAMES: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+    #[hook]
+    async fn unrecognised_command_hook(_: &Context, _: &Message, name: &str) {
+        UNRECOGNISED_NAMES.lock().unwrap().push(name.to_string());
+    }
+
+    #[tokio::test]
+    async fn unrecognised_command() {
+        let framework = StandardFramework::new()
+            .group(&GENERAL_GROUP)
+            .unrecognised_command(unrecognised_command_hook);
+
+        dispatch(&framework, message("~pong foo")).await;
+        dispatch(&framework, message("~ping")).await;
+        dispatch(&framework, message("pong")).await;
+
+        assert_eq!(*UNRECOGNISED_NAMES.lock().unwrap(), vec!["pong".to_string()]);
+    }
+
+    static UNDISPATCHED: std::sync::Mutex<Vec<(String, String)>> =
+        std::sync::Mutex::new(Vec::new());
+
+    #[hook]
+    async fn undispatched_command_hook(_: &Context, _: &Message, name: &str, args: Args) {
+        UNDISPATCHED.lock().unwrap().push((name.to_string(), args.rest().to_string()));
+    }
+
+    #[tokio::test]
+    async fn undispatched_command() {
+        let framework = StandardFramework::new()
+            .group(&GENERAL_GROUP)
+            .undispatched_command(undispatched_command_hook);
+        framework.configure(|c| c.disabled_commands(HashSet::from(["ping".to_string()])));
+
+        dispatch(&framework, message("~pong foo")).await;
+        dispatch(&framework, message("~ping bar")).await;
+
+        // The hook is given what was typed after the prefix, to echo the attempted input.
+        assert_eq!(*UNDISPATCHED.lock().unwrap(), vec![
+            ("pong".to_string(), "pong foo".to_string()),
+            ("ping".to_string(), "ping bar".to_string()),
+        ]);
+    }
+
+    static PREFIX_ONLY_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    #[hook]
+    async fn prefix_only_hook(_: &Context, _: &Message) {
+        PREFIX_ONLY_CALLS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn prefix_only() {
+        let framework = StandardFramework::new().group(&GENERAL_GROUP).prefix_only(prefix_only_hook);
+        framework.configure(|c| c.on_mention(Some(UserId::new(1))));
+
+        dispatch(&framework, message("~")).await;
+        dispatch(&framework, message("~  ")).await;
+        dispatch(&framework, message("<@1>")).await;
+        assert_eq!(PREFIX_ONLY_CALLS.load(Ordering::SeqCst), 3);
+
+        dispatch(&framework, message("~garbage")).await;
+        dispatch(&framework, message("<@1> garbage")).await;
+        dispatch(&framework, message("~ping")).await;
+        dispatch(&framework, message("hello")).await;
+        assert_eq!(PREFIX_ONLY_CALLS.load(Ordering::SeqCst), 3);
+    }
+
+    static PINGER_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["Pinger", "pi"],
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    static PINGER_GROUP: CommandGroup = CommandGroup {
+        name: "Pinger",
+        options: &GroupOptions {
+            commands: &[&PINGER_COMMAND, &PING_COMMAND],
+            ..GROUP_OPTIONS
+        },
+    };
+
+    #[test]
+    fn commands_starting_with() {
+        let framework = StandardFramework::new().group(&GENERAL_GROUP).group(&PINGER_GROUP);
+
+        assert_eq!(framework.commands_starting_with("pi"), vec!["pi", "ping"]);
+        assert_eq!(framework.commands_starting_with("Pi"), vec!["Pinger"]);
+        assert_eq!(framework.commands_starting_with(""), vec!["Pinger", "pi", "ping", "say hello"]);
+        assert!(framework.commands_starting_with("pong").is_empty());
+
+        let mut framework = StandardFramework::new();
+        framework.configure(|c| c.case_insensitivity(true));
+        framework.group_add(&PINGER_GROUP);
+
+        assert_eq!(framework.commands_starting_with("PIN"), vec!["Pinger", "ping"]);
+    }
+
+    #[tokio::test]
+    async fn custom_check() {
+        let framework = StandardFramework::new()
+            .group(&CHECKED_GROUP)
+            .on_dispatch_error(checked_dispatch_error);
+
+        dispatch(&framework, message("~checked")).await;
+        dispatch(&framework, message_from("~checked", 2)).await;
+
+        assert_eq!(CHECKED_INVOCATIONS.load(Ordering::SeqCst), 1);
+        assert_eq!(*CHECK_FAILURES.lock().unwrap(), vec!["premium"]);
+    }
+
+    static DISABLED_NAMES: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+    #[hook]
+    async fn disabled_dispatch_error(_: &Context, _: &Message, error: DispatchError, _: &str) {
+        if let DispatchError::CommandDisabled(name) = error {
+            DISABLED_NAMES.lock().unwrap().push(name);
+        }
+    }
+
+    #[tokio::test]
+    async fn disabled_command_name() {
+        let framework = StandardFramework::new()
+            .group(&GENERAL_GROUP)
+            .on_dispatch_error(disabled_dispatch_error);
+        framework.configure(|c| c.disabled_commands(HashSet::from(["ping".to_string()])));
+
+        dispatch(&framework, message("~ping")).await;
+        dispatch(&framework, message("~say hello")).await;
+
+        assert_eq!(*DISABLED_NAMES.lock().unwrap(), vec!["ping".to_string()]);
+    }
+
+    static MAINTENANCE: AtomicBool = AtomicBool::new(true);
+    static GLOBALLY_BLOCKED: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+    counting_command_group!(GUARDED_INVOCATIONS, GUARDED_COMMAND, GUARDED_GROUP, {
+        names: &["guarded"],
+    });
+
+    #[hook]
+    async fn maintenance_check(_: &Context, _: &Message) -> bool {
+        !MAINTENANCE.load(Ordering::SeqCst)
+    }
+
+    #[hook]
+    async fn global_dispatch_error(_: &Context, _: &Message, error: DispatchError, name: &str) {
+        if matches!(error, DispatchError::GloballyBlocked) {
+            GLOBALLY_BLOCKED.lock().unwrap().push(name.to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn global_check() {
+        let framework =
+            StandardFramework::new().group(&GUARDED_GROUP).on_dispatch_error(global_dispatch_error);
+        framework.configure(|c| c.global_check(maintenance_check));
+
+        dispatch(&framework, message("~guarded")).await;
+        dispatch(&framework, message("~guarded foo")).await;
+        assert_eq!(GUARDED_INVOCATIONS.load(Ordering::SeqCst), 0);
+        assert_eq!(*GLOBALLY_BLOCKED.lock().unwrap(), vec!["guarded", "guarded"]);
+
+        MAINTENANCE.store(false, Ordering::SeqCst);
+        dispatch(&framework, message("~guarded")).await;
+        assert_eq!(GUARDED_INVOCATIONS.load(Ordering::SeqCst), 1);
+        assert_eq!(GLOBALLY_BLOCKED.lock().unwrap().len(), 2);
+    }
+
+    static BLOCKED: std::sync::Mutex<Vec<(String, String)>> = std::sync::Mutex::new(Vec::new());
+
+    counting_command_group!(BLOCKABLE_INVOCATIONS, BLOCKABLE_COMMAND, BLOCKABLE_GROUP, {
+        names: &["blockable"],
+    });
+
+    #[hook]
+    async fn blocked_dispatch_error(_: &Context, _: &Message, error: DispatchError, name: &str) {
+        let kind = match error {
+            DispatchError::BlockedUser => "user",
+            DispatchError::BlockedGuild => "guild",
+            _ => return,
+        };
+        BLOCKED.lock().unwrap().push((kind.to_string(), name.to_string()));
+    }
+
+    #[tokio::test]
+    async fn blocked_users_and_guilds() {
+        let framework = StandardFramework::new()
+            .group(&BLOCKABLE_GROUP)
+            .on_dispatch_error(blocked_dispatch_error);
+        framework.configure(|c| c.block_user(UserId::new(1)).block_guild(GuildId::new(2)));
+
+        let blocked_user = message_from("~blockable", 1);
+
+        let mut blocked_guild = message_from("~blockable", 3);
+        blocked_guild.guild_id = Some(GuildId::new(2));
+
+        let mut unblocked = message_from("~blockable", 3);
+        unblocked.guild_id = Some(GuildId::new(4));
+
+        dispatch(&framework, blocked_user.clone()).await;
+        dispatch(&framework, blocked_guild).await;
+        assert_eq!(BLOCKABLE_INVOCATIONS.load(Ordering::SeqCst), 0);
+        assert_eq!(*BLOCKED.lock().unwrap(), vec![
+            ("user".to_string(), "blockable".to_string()),
+            ("guild".to_string(), "blockable".to_string()),
+        ]);
+
+        dispatch(&framework, unblocked).await;
+        assert_eq!(BLOCKABLE_INVOCATIONS.load(Ordering::SeqCst), 1);
+
+        // Owners are exempt from blocks.
+        framework.configure(|c| c.owners(HashSet::from([UserId::new(1)])));
+        dispatch(&framework, blocked_user.clone()).await;
+        assert_eq!(BLOCKABLE_INVOCATIONS.load(Ordering::SeqCst), 2);
+
+        framework.configure(|c| c.owners(HashSet::new()).unblock_user(UserId::new(1)));
+        dispatch(&framework, blocked_user).await;
+        assert_eq!(BLOCKABLE_INVOCATIONS.load(Ordering::SeqCst), 3);
+        assert_eq!(BLOCKED.lock().unwrap().len(), 2);
+    }
+
+    static ADMIN_BAN_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["ban", "b"],
+            desc: Some("Bans a member."),
+            required_permissions: Permissions::BAN_MEMBERS,
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    static ADMIN_GROUP: CommandGroup = CommandGroup {
+        name: "Admin",
+        options: &GroupOptions {
+            prefixes: &["admin"],
+            description: Some("Moderation."),
+            required_permissions: Permissions::ADMINISTRATOR,
+            commands: &[&ADMIN_BAN_COMMAND],
+            sub_groups: &[&GENERAL_GROUP],
+            ..GROUP_OPTIONS
+        },
+    };
+
+    #[test]
+    fn registry() {
+        let framework = StandardFramework::new().group(&ADMIN_GROUP).group(&PINGER_GROUP);
+
+        let groups = framework.registry().collect::<Vec<_>>();
+        let names = groups.iter().map(GroupView::name).collect::<Vec<_>>();
+        assert_eq!(names, vec!["Admin", "Pinger"]);
+
+        let admin = groups[0];
+        assert!(std::ptr::eq(admin.group(), &ADMIN_GROUP));
+        assert_eq!(admin.options().description, Some("Moderation."));
+        assert_eq!(*admin.options().required_permissions(), Permissions::ADMINISTRATOR);
+
+        let commands = admin.commands().collect::<Vec<_>>();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].names(), &["ban", "b"]);
+        assert_eq!(commands[0].options().desc, Some("Bans a member."));
+        assert_eq!(*commands[0].options().required_permissions(), Permissions::BAN_MEMBERS);
+        assert_eq!(commands[0].sub_commands().count(), 0);
+
+        let sub_groups = admin.sub_groups().collect::<Vec<_>>();
+        assert_eq!(sub_groups.len(), 1);
+        assert_eq!(sub_groups[0].name(), "General");
+        let names = sub_groups[0].commands().map(|c| c.names()[0]).collect::<Vec<_>>();
+        assert_eq!(names, vec!["ping", "say hello"]);
+        assert_eq!(sub_groups[0].sub_groups().count(), 0);
+    }
+}
@@ -23,11 +23,15 @@ pub struct CommandMap {
     cmds: HashMap<String, (&'static Command, Arc<CommandMap>)>,
     min_length: usize,
     max_length: usize,
+    case_folding: Option<CaseFolding>,
 }
 
 impl CommandMap {
     pub fn new(cmds: &[&'static Command], conf: &Configuration) -> Self {
-        let mut map = Self::default();
+        let mut map = Self {
+            case_folding: conf.case_insensitive_commands.then_some(conf.case_folding),
+            ..Self::default()
+        };
 
         for cmd in cmds {
             let sub_map = Arc::new(Self::new(cmd.options.sub_commands, conf));
@@ -37,8 +41,11 @@ impl CommandMap {
                 map.min_length = std::cmp::min(len, map.min_length);
                 map.max_length = std::cmp::max(len, map.max_length);
 
-                let name =
-                    if conf.case_insensitive { name.to_lowercase() } else { (*name).to_string() };
+                let name = if conf.case_insensitive_commands {
+                    conf.case_folding.fold(name)
+                } else {
+                    (*name).to_string()
+                };
 
                 map.cmds.insert(name, (*cmd, Arc::clone(&sub_map)));
             }
@@ -46,6 +53,28 @@ impl CommandMap {
 
         map
     }
+
+    /// Returns the sorted names of the commands in this map that start with `prefix`, compared
+    /// case-insensitively if the map was created with case insensitivity enabled.
+    pub fn starts_with(&self, prefix: &str) -> Vec<&'static str> {
+        let fold = |name: &str| match self.case_folding {
+            Some(case_folding) => case_folding.fold(name),
+            None => name.to_string(),
+        };
+        let prefix = fold(prefix);
+
+        let mut names = self
+            .cmds
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .filter_map(|(key, (cmd, _))| {
+                cmd.options.names.iter().copied().find(|name| fold(name) == *key)
+            })
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
 }
 
 impl ParseMap for CommandMap {
@@ -74,7 +103,7 @@ impl ParseMap for CommandMap {
 
 #[derive(Debug, Default)]
 pub struct GroupMap {
-    groups: HashMap<&'static str, (&'static CommandGroup, Arc<GroupMap>, Arc<CommandMap>)>,
+    groups: HashMap<String, (&'static CommandGroup, Arc<GroupMap>, Arc<CommandMap>)>,
     min_length: usize,
     max_length: usize,
 }
@@ -92,8 +121,14 @@ impl GroupMap {
                 map.min_length = std::cmp::min(len, map.min_length);
                 map.max_length = std::cmp::max(len, map.max_length);
 
+                let prefix = if conf.case_insensitive_commands {
+                    conf.case_folding.fold(prefix)
+                } else {
+                    (*prefix).to_string()
+                };
+
                 map.groups.insert(
-                    *prefix,
+                    prefix,
                     (*group, Arc::clone(&subgroups_map), Arc::clone(&commands_map)),
                 );
             }
@@ -118,7 +153,7 @@ impl ParseMap for GroupMap {
 
     #[inline]
     fn get(&self, name: &str) -> Option<Self::Storage> {
-        self.groups.get(&name).cloned()
+        self.groups.get(name).cloned()
     }
 
     #[inline]
@@ -10,6 +10,7 @@ use std::collections::HashMap;
 
 use futures::future::{BoxFuture, FutureExt};
 use map::{CommandMap, GroupMap, ParseMap};
+use tracing::{instrument, Instrument};
 use uwl::Stream;
 
 // FIXME: Add the `http` parameter to `Guild::user_permissions_in`.
@@ -98,24 +99,105 @@ fn permissions_in(
 }
 
 #[inline]
-fn to_lowercase<'a>(config: &Configuration, s: &'a str) -> Cow<'a, str> {
-    if config.case_insensitive {
-        Cow::Owned(s.to_lowercase())
+fn fold_case<'a>(config: &Configuration, case_insensitive: bool, s: &'a str) -> Cow<'a, str> {
+    if case_insensitive {
+        Cow::Owned(config.case_folding.fold(s))
     } else {
         Cow::Borrowed(s)
     }
 }
 
-/// Parse a mention in the message that is of either the direct (`<@id>`) or nickname (`<@!id>`)
-/// syntax, and compare the encoded `id` with the id from [`Configuration::on_mention`] for a
-/// match. Returns `Some(<id>)` on success, [`None`] otherwise.
-pub fn mention<'a>(stream: &mut Stream<'a>, config: &Configuration) -> Option<&'a str> {
-    let on_mention = config.on_mention.as_deref()?;
+/// Folds the case of a prefix or suffix, if [`Configuration::case_insensitive_prefixes`] is set.
+#[inline]
+fn fold_prefix<'a>(config: &Configuration, s: &'a str) -> Cow<'a, str> {
+    fold_case(config, config.case_insensitive_prefixes, s)
+}
+
+/// Folds the case of a command name or group prefix, if
+/// [`Configuration::case_insensitive_commands`] is set.
+#[inline]
+fn fold_command<'a>(config: &Configuration, s: &'a str) -> Cow<'a, str> {
+    fold_case(config, config.case_insensitive_commands, s)
+}
+
+/// Peeks the input in the stream that `f` transforms into `name`, if any.
+///
+/// Case folding may lengthen a string, such as `ß` to `ss`, but never shortens one, so as many
+/// characters as `name` has are peeked, then shortened and transformed again until they match.
+fn peek_folded<'a>(
+    stream: &Stream<'a>,
+    name: &str,
+    f: impl Fn(&str) -> Cow<'_, str>,
+) -> Option<&'a str> {
+    let mut raw = stream.peek_for_char(name.chars().count());
+
+    loop {
+        let n = f(raw);
+        if n == name {
+            return Some(raw);
+        }
+
+        if raw.is_empty() || n.len() < name.len() {
+            return None;
+        }
+
+        raw = &raw[..raw.char_indices().last().map_or(0, |(i, _)| i)];
+    }
+}
 
-    let start = stream.offset();
+/// A position in a [`Stream`] to backtrack to, such as after failing to parse something that only
+/// partially matched.
+///
+/// Checkpoints are plain offsets, so they can be nested and restored any number of times.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[must_use]
+struct Checkpoint(usize);
+
+impl Checkpoint {
+    /// Remembers the current position of the stream.
+    fn new(stream: &Stream<'_>) -> Self {
+        Self(stream.offset())
+    }
+
+    /// Moves the stream back to the remembered position.
+    fn restore(self, stream: &mut Stream<'_>) {
+        stream.set(self.0);
+    }
+}
+
+/// The outcome of parsing a user mention with [`mention`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MentionResult<'a> {
+    /// The message does not start with a well-formed user mention.
+    None,
+    /// The message starts with a mention of a user that is not one of
+    /// [`Configuration::on_mention`], such as another bot.
+    OtherBot(&'a str),
+    /// The message starts with a mention of one of [`Configuration::on_mention`].
+    Ours(&'a str),
+}
+
+impl<'a> MentionResult<'a> {
+    /// Returns the mentioned id if the mention is one of [`Configuration::on_mention`].
+    #[must_use]
+    pub fn ours(self) -> Option<&'a str> {
+        match self {
+            Self::Ours(id) => Some(id),
+            Self::None | Self::OtherBot(_) => None,
+        }
+    }
+}
+
+/// Parse a mention in the message that is of either the direct (`<@id>`) or nickname (`<@!id>`)
+/// syntax, and compare the encoded `id` with the ids from [`Configuration::on_mention`] for a
+/// match.
+///
+/// The stream is only advanced past the mention if it is [`MentionResult::Ours`].
+pub fn mention<'a>(stream: &mut Stream<'a>, config: &Configuration) -> MentionResult<'a> {
+    let start = Checkpoint::new(stream);
 
     if !stream.eat("<@") {
-        return None;
+        return MentionResult::None;
     }
 
     // Optional.
@@ -123,55 +205,107 @@ pub fn mention<'a>(stream: &mut Stream<'a>, config: &Configuration) -> Option<&'
 
     let id = stream.take_while(|b| b.is_ascii_digit());
 
-    if !stream.eat(">") {
+    if id.is_empty() || !stream.eat(">") {
         // Backtrack to where we were.
-        stream.set(start);
+        start.restore(stream);
 
+        return MentionResult::None;
+    }
+
+    if config.on_mention.contains(id) {
+        MentionResult::Ours(id)
+    } else {
+        start.restore(stream);
+
+        MentionResult::OtherBot(id)
+    }
+}
+
+/// Parse a role mention in the message (`<@&id>`), and compare the encoded `id` with the id from
+/// [`Configuration::on_role_mention`] for a match. Returns `Some(<id>)` on success, [`None`]
+/// otherwise.
+pub fn role_mention<'a>(stream: &mut Stream<'a>, config: &Configuration) -> Option<&'a str> {
+    let on_role_mention = config.on_role_mention.as_deref()?;
+
+    let start = Checkpoint::new(stream);
+
+    if !stream.eat("<@&") {
         return None;
     }
 
-    if id == on_mention {
+    let id = stream.take_while(|b| b.is_ascii_digit());
+
+    if stream.eat(">") && id == on_role_mention {
         Some(id)
     } else {
-        stream.set(start);
+        // Backtrack to where we were.
+        start.restore(stream);
 
         None
     }
 }
 
+/// Runs the [`Configuration::dynamic_prefix`] hooks, reusing their results for the guild if
+/// they are cached.
+async fn dynamic_prefixes(ctx: &Context, msg: &Message, config: &Configuration) -> Vec<String> {
+    #[cfg(feature = "cache")]
+    let cache = config.prefix_cache.as_ref().zip(msg.guild_id);
+    #[cfg(feature = "cache")]
+    if let Some(prefixes) = cache.and_then(|(cache, guild_id)| cache.get(guild_id)) {
+        return prefixes;
+    }
+
+    let mut prefixes = Vec::new();
+    for f in &config.dynamic_prefixes {
+        prefixes.extend(f(ctx, msg).await);
+    }
+
+    #[cfg(feature = "cache")]
+    if let Some((cache, guild_id)) = cache {
+        cache.insert(guild_id, prefixes.clone());
+    }
+
+    prefixes
+}
+
 async fn find_prefix<'a>(
     ctx: &Context,
     msg: &Message,
     config: &Configuration,
     stream: &Stream<'a>,
 ) -> Option<Cow<'a, str>> {
-    let try_match = |prefix: &str| {
-        let peeked = stream.peek_for_char(prefix.chars().count());
-        let peeked = to_lowercase(config, peeked);
-        (prefix == peeked).then_some(peeked)
-    };
-
-    for f in &config.dynamic_prefixes {
-        if let Some(p) = f(ctx, msg).await {
-            let p = to_lowercase(config, &p);
-            if let Some(p) = try_match(&p) {
-                return Some(p);
+    let try_match =
+        |prefix: &str| peek_folded(stream, prefix, |s| fold_prefix(config, s)).map(Cow::Borrowed);
+
+    // The longest matching prefix wins, whether dynamic or static, so that a prefix which starts
+    // with another one is not cut short, such as `!!` when `!` is also registered. Dynamic
+    // prefixes are tried first, so they win ties.
+    let dynamic = dynamic_prefixes(ctx, msg, config).await;
+    let mut longest: Option<Cow<'a, str>> = None;
+    for p in dynamic.iter().chain(&config.prefixes) {
+        if let Some(p) = try_match(&fold_prefix(config, p)) {
+            if !longest.as_ref().is_some_and(|longest| longest.len() >= p.len()) {
+                longest = Some(p);
             }
         }
     }
 
-    config.prefixes.iter().find_map(|p| try_match(p))
+    longest
 }
 
 /// Parse a prefix in the message.
 ///
 /// The "prefix" may be one of the following:
 /// - A mention (`<@id>`/`<@!id>`)
+/// - A role mention (`<@&id>`)
 /// - A dynamically constructed prefix ([`Configuration::dynamic_prefix`])
 /// - A static prefix ([`Configuration::prefix`])
-/// - Nothing
+/// - Nothing, in private channels if [`Configuration::no_dm_prefix`] is enabled, in which case an
+///   empty prefix is returned
 ///
-/// In all cases, whitespace after the prefix is cleared.
+/// In all cases, whitespace after the prefix is cleared, as determined by
+/// [`Configuration::whitespace`]. If [`Configuration::require_prefix_touches_command`] is enabled,
+/// a prefix followed by whitespace is not matched at all, except for mentions.
 #[allow(clippy::needless_lifetimes)] // Clippy and the compiler disagree
 pub async fn prefix<'a>(
     ctx: &Context,
@@ -179,57 +313,177 @@ pub async fn prefix<'a>(
     stream: &mut Stream<'a>,
     config: &Configuration,
 ) -> Option<Cow<'a, str>> {
-    if let Some(id) = mention(stream, config) {
-        stream.take_while_char(char::is_whitespace);
+    let mention = mention(stream, config);
+    if let MentionResult::OtherBot(id) = mention {
+        tracing::debug!("Message starts with a mention of {}, which is not the bot", id);
+    }
+
+    if let Some(id) = mention.ours().or_else(|| role_mention(stream, config)) {
+        stream.take_while_char(config.whitespace);
 
         return Some(Cow::Borrowed(id));
     }
 
-    let prefix = find_prefix(ctx, msg, config, stream).await;
+    let mut prefix = find_prefix(ctx, msg, config, stream).await;
 
     if let Some(prefix) = &prefix {
         stream.increment(prefix.len());
+
+        if config.require_prefix_touches_command
+            && stream.peek_for_char(1).chars().any(config.whitespace)
+        {
+            return None;
+        }
+    } else if config.no_dm_prefix && config.allow_dm && msg.is_private() {
+        prefix = Some(Cow::Borrowed(""));
     }
 
     if config.with_whitespace.prefixes {
-        stream.take_while_char(char::is_whitespace);
+        stream.take_while_char(config.whitespace);
     }
 
     prefix
 }
 
+/// Strip a suffix off the end of the message.
+///
+/// If [`Configuration::suffixes`] is empty, the content is returned untouched. Otherwise the
+/// content must end with one of the suffixes, which is removed along with any whitespace around
+/// it. Returns [`None`] if none of the suffixes match.
+#[must_use]
+pub fn suffix<'a>(content: &'a str, config: &Configuration) -> Option<&'a str> {
+    if config.suffixes.is_empty() {
+        return Some(content);
+    }
+
+    let content = content.trim_end();
+
+    config.suffixes.iter().find_map(|suffix| {
+        let start = content.len().checked_sub(suffix.len())?;
+        let tail = content.get(start..)?;
+
+        (fold_prefix(config, tail) == fold_prefix(config, suffix))
+            .then(|| content[..start].trim_end())
+    })
+}
+
+/// Skip the start of the message, as many bytes as [`Configuration::leading_skip`] returns.
+///
+/// Returns [`None`] if the skipped length is past the end of the content or not on a character
+/// boundary.
+pub fn leading_skip<'a>(content: &'a str, config: &Configuration) -> Option<&'a str> {
+    let Some(leading_skip) = config.leading_skip else { return Some(content) };
+
+    let skip = leading_skip(content);
+    let rest = content.get(skip..);
+    if rest.is_none() {
+        tracing::warn!("Cannot skip {} bytes of a message of {} bytes", skip, content.len());
+    }
+
+    rest
+}
+
+/// The user invoking a command and where they invoke it from, as checked by
+/// [`check_command_access`].
+///
+/// This can be built from a [`Message`] as well as from a [`CommandInteraction`], so that commands
+/// dispatched from interactions are held to the same rules as text commands.
+#[derive(Clone, Copy, Debug)]
+pub struct AccessContext {
+    pub user_id: UserId,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+}
+
+impl AccessContext {
+    fn is_private(&self) -> bool {
+        self.guild_id.is_none()
+    }
+}
+
+impl From<&Message> for AccessContext {
+    fn from(msg: &Message) -> Self {
+        Self {
+            user_id: msg.author.id,
+            channel_id: msg.channel_id,
+            guild_id: msg.guild_id,
+        }
+    }
+}
+
+impl From<&CommandInteraction> for AccessContext {
+    fn from(interaction: &CommandInteraction) -> Self {
+        Self {
+            user_id: interaction.user.id,
+            channel_id: interaction.channel_id,
+            guild_id: interaction.guild_id,
+        }
+    }
+}
+
 /// Checked per valid group or command in the message.
+#[instrument(level = "debug", skip_all)]
 async fn check_discrepancy(
-    #[allow(unused_variables)] ctx: &Context,
+    ctx: &Context,
     msg: &Message,
     config: &Configuration,
     options: &impl CommonOptions,
 ) -> Result<(), DispatchError> {
-    if options.owners_only() && !config.owners.contains(&msg.author.id) {
+    check_command_access(ctx, AccessContext::from(msg), config, options).await
+}
+
+/// Checks whether a command or group with the given options may be invoked, enforcing the
+/// ownership, channel scope, permission and role restrictions of the options.
+///
+/// This is what the framework checks for each group and command of a message, and can be used to
+/// apply the same restrictions to commands invoked otherwise, such as through interactions.
+///
+/// # Errors
+///
+/// Returns the [`DispatchError`] describing the first restriction that is not met.
+pub async fn check_command_access(
+    #[allow(unused_variables)] ctx: &Context,
+    access: AccessContext,
+    config: &Configuration,
+    options: &impl CommonOptions,
+) -> Result<(), DispatchError> {
+    if options.owners_only() && !config.owners.contains(&access.user_id) {
         return Err(DispatchError::OnlyForOwners);
     }
 
-    if options.only_in() == OnlyIn::Dm && !msg.is_private() {
+    if options.only_in() == OnlyIn::Dm && !access.is_private() {
         return Err(DispatchError::OnlyForDM);
     }
 
-    if (!config.allow_dm || options.only_in() == OnlyIn::Guild) && msg.is_private() {
+    if (!config.allow_dm || options.only_in() == OnlyIn::Guild) && access.is_private() {
         return Err(DispatchError::OnlyForGuilds);
     }
 
+    if access.is_private()
+        && !options.required_permissions().is_empty()
+        && !(options.owner_privilege() && config.owners.contains(&access.user_id))
+    {
+        return Err(DispatchError::PermissionsUnavailableInDM(*options.required_permissions()));
+    }
+
     #[cfg(feature = "cache")]
     {
-        if let Some(guild_id) = msg.guild_id {
+        if let Some(guild_id) = access.guild_id {
             let roles = match ctx.cache.guild(guild_id) {
                 Some(guild) => guild.roles.clone(),
                 None => return Ok(()),
             };
 
-            let Ok(member) = guild_id.member(ctx, msg.author.id).await else {return Ok(())};
-            let perms = permissions_in(ctx, guild_id, msg.channel_id, &member, &roles);
+            let Ok(member) = guild_id.member(ctx, access.user_id).await else {return Ok(())};
+
+            if config.ignore_timed_out_members && is_timed_out(&member, Timestamp::now()) {
+                return Err(DispatchError::UserTimedOut);
+            }
+
+            let perms = permissions_in(ctx, guild_id, access.channel_id, &member, &roles);
 
             if !(perms.contains(*options.required_permissions())
-                || options.owner_privilege() && config.owners.contains(&msg.author.id))
+                || options.owner_privilege() && config.owners.contains(&access.user_id))
             {
                 return Err(DispatchError::LackingPermissions(*options.required_permissions()));
             }
@@ -237,26 +491,62 @@ async fn check_discrepancy(
             if !perms.administrator() && !has_correct_roles(options, &roles, &member) {
                 return Err(DispatchError::LackingRole);
             }
+
+            // Owners have no privilege over what the bot itself is allowed to do.
+            let bot_required = *options.bot_required_permissions();
+            if !bot_required.is_empty() {
+                let bot_id = ctx.cache.current_user().id;
+                if let Ok(bot) = guild_id.member(ctx, bot_id).await {
+                    let perms = permissions_in(ctx, guild_id, access.channel_id, &bot, &roles);
+
+                    if !perms.contains(bot_required) {
+                        return Err(DispatchError::BotLackingPermissions(bot_required));
+                    }
+                }
+            }
         }
     }
 
     Ok(())
 }
 
+/// Whether the member's timeout has not yet run out at `now`.
+#[cfg(feature = "cache")]
+fn is_timed_out(member: &Member, now: Timestamp) -> bool {
+    member.communication_disabled_until.is_some_and(|until| until > now)
+}
+
+/// Returns the name as transformed by `f`, the length in bytes of the untransformed name in the
+/// stream, and the entry the name maps to, if any.
 fn try_parse<M: ParseMap>(
     stream: &mut Stream<'_>,
     map: &M,
-    by_space: bool,
+    config: &Configuration,
     f: impl Fn(&str) -> String,
-) -> (String, Option<M::Storage>) {
-    if by_space {
-        let n = f(stream.peek_until_char(char::is_whitespace));
+) -> (String, usize, Option<M::Storage>) {
+    if config.quoted_commands {
+        if let Some(quoted) = stream.rest().strip_prefix('"') {
+            if let Some(end) = quoted.find('"') {
+                let raw = &quoted[..end];
+                let n = f(raw);
+
+                let o = map.get(&n);
+
+                return (n, raw.len() + 2, o);
+            }
+        }
+    }
+
+    if config.by_space {
+        let raw = stream.peek_until_char(config.whitespace);
+        let n = f(raw);
 
         let o = map.get(&n);
 
-        (n, o)
+        (n, raw.len(), o)
     } else {
-        let mut n = f(stream.peek_for_char(map.max_length()));
+        let mut raw = stream.peek_for_char(map.max_length());
+        let mut n = f(raw);
         let mut o = None;
 
         for _ in 0..(map.max_length() - map.min_length()) {
@@ -266,10 +556,13 @@ fn try_parse<M: ParseMap>(
                 break;
             }
 
-            n.pop();
+            // Case folding may change the length of the name, so shorten the untransformed name
+            // and transform it again.
+            raw = &raw[..raw.char_indices().last().map_or(0, |(i, _)| i)];
+            n = f(raw);
         }
 
-        (n, o)
+        (n, raw.len(), o)
     }
 }
 
@@ -279,29 +572,31 @@ fn parse_cmd<'a>(
     msg: &'a Message,
     config: &'a Configuration,
     map: &'a CommandMap,
+    depth: usize,
 ) -> BoxFuture<'a, Result<&'static Command, ParseError>> {
     async move {
-        let (n, r) =
-            try_parse(stream, map, config.by_space, |s| to_lowercase(config, s).into_owned());
+        let (n, len, r) = try_parse(stream, map, config, |s| fold_command(config, s).into_owned());
 
-        if config.disabled_commands.contains(&n) {
+        if config.disabled_commands.iter().any(|disabled| fold_command(config, disabled) == n) {
             return Err(ParseError::Dispatch {
-                error: DispatchError::CommandDisabled,
+                error: DispatchError::CommandDisabled(n.clone()),
                 command_name: n,
+                remainder: stream.rest().to_string(),
             });
         }
 
         if let Some((cmd, map)) = r {
-            stream.increment(n.len());
+            stream.increment(len);
 
             if config.with_whitespace.commands {
-                stream.take_while_char(char::is_whitespace);
+                stream.take_while_char(config.whitespace);
             }
 
             check_discrepancy(ctx, msg, config, &cmd.options).await.map_err(|e| {
                 ParseError::Dispatch {
                     error: e,
                     command_name: n,
+                    remainder: stream.rest().to_string(),
                 }
             })?;
 
@@ -309,13 +604,32 @@ fn parse_cmd<'a>(
                 return Ok(cmd);
             }
 
-            return match parse_cmd(stream, ctx, msg, config, &map).await {
-                Err(ParseError::UnrecognisedCommand(Some(_))) => Ok(cmd),
+            if depth >= config.max_command_depth {
+                // Only refuse to descend when the message actually names a deeper sub-command.
+                let (_, _, sub) =
+                    try_parse(stream, &*map, config, |s| fold_command(config, s).into_owned());
+                if sub.is_none() {
+                    return Ok(cmd);
+                }
+
+                return Err(ParseError::UnrecognisedCommand {
+                    name: None,
+                    remainder: stream.rest().to_string(),
+                });
+            }
+
+            return match parse_cmd(stream, ctx, msg, config, &map, depth + 1).await {
+                Err(ParseError::UnrecognisedCommand {
+                    name: Some(_), ..
+                }) => Ok(cmd),
                 res => res,
             };
         }
 
-        Err(ParseError::UnrecognisedCommand(Some(n.to_string())))
+        Err(ParseError::UnrecognisedCommand {
+            name: Some(n),
+            remainder: stream.rest().to_string(),
+        })
     }
     .boxed()
 }
@@ -326,21 +640,25 @@ fn parse_group<'a>(
     msg: &'a Message,
     config: &'a Configuration,
     map: &'a GroupMap,
+    depth: usize,
+    path: &'a mut Vec<&'static CommandGroup>,
 ) -> BoxFuture<'a, Result<(&'static CommandGroup, Arc<CommandMap>), ParseError>> {
     async move {
-        let (n, o) = try_parse(stream, map, config.by_space, ToString::to_string);
+        let (n, len, o) = try_parse(stream, map, config, |s| fold_command(config, s).into_owned());
 
         if let Some((group, map, commands)) = o {
-            stream.increment(n.len());
+            stream.increment(len);
+            path.push(group);
 
             if config.with_whitespace.groups {
-                stream.take_while_char(char::is_whitespace);
+                stream.take_while_char(config.whitespace);
             }
 
             check_discrepancy(ctx, msg, config, &group.options).await.map_err(|e| {
                 ParseError::Dispatch {
                     error: e,
-                    command_name: n,
+                    command_name: n.clone(),
+                    remainder: stream.rest().to_string(),
                 }
             })?;
 
@@ -348,13 +666,32 @@ fn parse_group<'a>(
                 return Ok((group, commands));
             }
 
-            return match parse_group(stream, ctx, msg, config, &map).await {
-                Err(ParseError::UnrecognisedCommand(None)) => Ok((group, commands)),
+            if depth >= config.max_command_depth {
+                // Only refuse to descend when the message actually names a deeper sub-group.
+                let (_, _, sub) =
+                    try_parse(stream, &*map, config, |s| fold_command(config, s).into_owned());
+                if sub.is_none() {
+                    return Ok((group, commands));
+                }
+
+                return Err(ParseError::UnrecognisedCommand {
+                    name: Some(n),
+                    remainder: stream.rest().to_string(),
+                });
+            }
+
+            return match parse_group(stream, ctx, msg, config, &map, depth + 1, path).await {
+                Err(ParseError::UnrecognisedCommand {
+                    name: None, ..
+                }) => Ok((group, commands)),
                 res => res,
             };
         }
 
-        Err(ParseError::UnrecognisedCommand(None))
+        Err(ParseError::UnrecognisedCommand {
+            name: None,
+            remainder: stream.rest().to_string(),
+        })
     }
     .boxed()
 }
@@ -368,7 +705,7 @@ async fn handle_command<'a>(
     map: &'a CommandMap,
     group: &'static CommandGroup,
 ) -> Result<Invoke, ParseError> {
-    match parse_cmd(stream, ctx, msg, config, map).await {
+    match parse_cmd(stream, ctx, msg, config, map, 0).await {
         Ok(command) => Ok(Invoke::Command {
             group,
             command,
@@ -379,6 +716,7 @@ async fn handle_command<'a>(
                     ParseError::Dispatch {
                         error: e,
                         command_name: command.options.names[0].to_string(),
+                        remainder: stream.rest().to_string(),
                     }
                 })?;
 
@@ -399,33 +737,57 @@ async fn handle_group<'a>(
     msg: &'a Message,
     config: &'a Configuration,
     map: &'a GroupMap,
+    group_helps: GroupHelps<'_>,
 ) -> Result<Invoke, ParseError> {
-    match parse_group(stream, ctx, msg, config, map).await {
-        Ok((group, map)) => handle_command(stream, ctx, msg, config, &map, group).await,
-        Err(error) => Err(error),
+    // The groups whose prefixes were consumed, from the outermost to the innermost.
+    let mut path = Vec::new();
+    let (group, map) = parse_group(stream, ctx, msg, config, map, 0, &mut path).await?;
+
+    if config.help_takes_precedence {
+        if let Some(help) = group_help(stream, config, &path, group_helps) {
+            return Ok(help);
+        }
     }
+
+    let res = handle_command(stream, ctx, msg, config, &map, group).await;
+
+    if !config.help_takes_precedence && is_unrecognised(&res) {
+        if let Some(help) = group_help(stream, config, &path, group_helps) {
+            return Ok(help);
+        }
+    }
+
+    res
 }
 
+/// Both variants carry the `remainder` of the message at the point of failure, that is, the
+/// input from where the parser stopped onwards.
 #[derive(Debug)]
 pub enum ParseError {
-    UnrecognisedCommand(Option<String>),
-    Dispatch { error: DispatchError, command_name: String },
+    UnrecognisedCommand { name: Option<String>, remainder: String },
+    Dispatch { error: DispatchError, command_name: String, remainder: String },
 }
 
 fn is_unrecognised<T>(res: &Result<T, ParseError>) -> bool {
-    matches!(res, Err(ParseError::UnrecognisedCommand(_)))
+    matches!(res, Err(ParseError::UnrecognisedCommand { .. }))
 }
 
 /// Parse a command from the message.
 ///
 /// The "command" may be:
 /// 1. A *help command* that provides a friendly browsing interface of all groups and commands,
-///    explaining what each of them are, how they are laid out and how to invoke them. There can
-///    only one help command registered, but might have many names defined for invocation of itself.
+///    explaining what each of them are, how they are laid out and how to invoke them. A help
+///    command might have many names defined for invocation of itself. Besides the global one set
+///    with `help_was_set`, help commands in `group_helps` are scoped to a group, and are only
+///    invoked after its prefix. The help of the innermost group whose prefix was given wins.
 ///
 /// 2. A command defined under another command or a group, which may also belong to another group
 ///    and so on. To invoke this command, all names and prefixes of its parent commands and groups
 ///    must be specified before it.
+///
+/// Parsing runs in a `parse_command` [`tracing`] span carrying the IDs of the guild, channel and
+/// author of the message, and the name of the resolved command once known. The content of the
+/// message is left out.
 pub async fn command(
     ctx: &Context,
     msg: &Message,
@@ -433,34 +795,79 @@ pub async fn command(
     groups: &[(&'static CommandGroup, Map)],
     config: &Configuration,
     help_was_set: Option<&[&'static str]>,
+    group_helps: GroupHelps<'_>,
 ) -> Result<Invoke, ParseError> {
-    // Precedence is taken over commands named as one of the help names.
-    if let Some(names) = help_was_set {
-        for name in names {
-            let n = to_lowercase(config, stream.peek_for_char(name.chars().count()));
-
-            if name == &n {
-                stream.increment(n.len());
+    let span = tracing::info_span!(
+        "parse_command",
+        guild_id = ?msg.guild_id,
+        channel_id = %msg.channel_id,
+        author_id = %msg.author.id,
+        command = tracing::field::Empty,
+    );
+    let res = resolve_command(ctx, msg, stream, groups, config, help_was_set, group_helps)
+        .instrument(span.clone())
+        .await;
+
+    let name = match &res {
+        Ok(Invoke::Command {
+            command, ..
+        }) => Some(command.options.names[0]),
+        Ok(Invoke::Help {
+            name, ..
+        }) => Some(*name),
+        Err(ParseError::Dispatch {
+            command_name, ..
+        }) => Some(command_name.as_str()),
+        Err(ParseError::UnrecognisedCommand {
+            ..
+        }) => None,
+    };
+    if let Some(name) = name {
+        span.record("command", name);
+    }
 
-                stream.take_while_char(char::is_whitespace);
+    res
+}
 
-                return Ok(Invoke::Help(name));
-            }
+async fn resolve_command(
+    ctx: &Context,
+    msg: &Message,
+    stream: &mut Stream<'_>,
+    groups: &[(&'static CommandGroup, Map)],
+    config: &Configuration,
+    help_was_set: Option<&[&'static str]>,
+    group_helps: GroupHelps<'_>,
+) -> Result<Invoke, ParseError> {
+    // Precedence is taken over commands named as one of the help names, unless disabled.
+    if config.help_takes_precedence {
+        if let Some(name) = help(stream, config, help_was_set) {
+            return Ok(Invoke::Help {
+                name,
+                group: None,
+            });
         }
     }
 
-    let mut last = Err::<Invoke, _>(ParseError::UnrecognisedCommand(None));
+    let mut last = Err::<Invoke, _>(ParseError::UnrecognisedCommand {
+        name: None,
+        remainder: stream.rest().to_string(),
+    });
     let mut is_prefixless = false;
 
     for (group, map) in groups {
+        // A group may consume part of the message before failing to recognise a command, such as
+        // the prefix of one of its sub-groups, so every attempt starts over from here.
+        let start = Checkpoint::new(stream);
+
         match map {
             // Includes [group] itself.
             Map::WithPrefixes(map) => {
-                let res = handle_group(stream, ctx, msg, config, map).await;
+                let res = handle_group(stream, ctx, msg, config, map, group_helps).await;
 
                 if !is_unrecognised(&res) {
                     return res;
                 }
+                start.restore(stream);
 
                 if !is_prefixless {
                     last = res;
@@ -472,8 +879,12 @@ pub async fn command(
                         Ok(Invoke::Command {
                             command, ..
                         }) => Some(command.options.names[0]),
-                        Ok(Invoke::Help(name)) => Some(name), // unreachable; fallback just in case
-                        Err(ParseError::UnrecognisedCommand(_)) => None,
+                        Ok(Invoke::Help {
+                            name, ..
+                        }) => Some(name),
+                        Err(ParseError::UnrecognisedCommand {
+                            ..
+                        }) => None,
                         Err(ParseError::Dispatch {
                             command_name, ..
                         }) => Some(command_name),
@@ -482,17 +893,19 @@ pub async fn command(
 
                 is_prefixless = true;
 
-                let res = handle_group(stream, ctx, msg, config, subgroups).await;
+                let res = handle_group(stream, ctx, msg, config, subgroups, group_helps).await;
 
                 if let Some(command_name) = command_name_if_recognised(&res) {
                     check_discrepancy(ctx, msg, config, &group.options).await.map_err(|e| {
                         ParseError::Dispatch {
                             error: e,
                             command_name: command_name.to_owned(),
+                            remainder: stream.rest().to_string(),
                         }
                     })?;
                     return res;
                 }
+                start.restore(stream);
 
                 let res = handle_command(stream, ctx, msg, config, commands, group).await;
 
@@ -501,21 +914,1339 @@ pub async fn command(
                         ParseError::Dispatch {
                             error: e,
                             command_name: command_name.to_owned(),
+                            remainder: stream.rest().to_string(),
                         }
                     })?;
                     return res;
                 }
+                start.restore(stream);
 
                 last = res;
             },
         }
     }
 
+    if !config.help_takes_precedence && is_unrecognised(&last) {
+        if let Some(name) = help(stream, config, help_was_set) {
+            return Ok(Invoke::Help {
+                name,
+                group: None,
+            });
+        }
+    }
+
+    last
+}
+
+/// Resolves a command from an already split path of group prefixes and command names, such as
+/// `["admin", "kick"]` from a slash command, to the same command that parsing `admin kick` from a
+/// message would.
+///
+/// This lets the same groups back both text and slash commands. Unlike [`command`], there is no
+/// message to check the command against, so only [`Configuration::disabled_commands`] is
+/// enforced, and help commands are not resolved.
+pub fn command_path(
+    groups: &[(&'static CommandGroup, Map)],
+    path: &[&str],
+    config: &Configuration,
+) -> Result<Invoke, ParseError> {
+    let mut last = Err(ParseError::UnrecognisedCommand {
+        name: None,
+        remainder: path.join(" "),
+    });
+
+    for (group, map) in groups {
+        let res = match map {
+            Map::WithPrefixes(map) => path_group(map, path, config, 0),
+            Map::Prefixless(subgroups, commands) => {
+                let res = path_group(subgroups, path, config, 0);
+                if !is_unrecognised(&res) {
+                    return res;
+                }
+
+                path_command(commands, path, config, group)
+            },
+        };
+
+        if !is_unrecognised(&res) {
+            return res;
+        }
+        last = res;
+    }
+
     last
 }
 
+fn path_group(
+    map: &GroupMap,
+    path: &[&str],
+    config: &Configuration,
+    depth: usize,
+) -> Result<Invoke, ParseError> {
+    let found = path.split_first().and_then(|(prefix, rest)| {
+        map.get(&fold_command(config, prefix)).map(|storage| (storage, rest))
+    });
+    let Some(((group, subgroups, commands), rest)) = found else {
+        return Err(ParseError::UnrecognisedCommand {
+            name: None,
+            remainder: path.join(" "),
+        });
+    };
+
+    if !subgroups.is_empty() && depth < config.max_command_depth {
+        let res = path_group(&subgroups, rest, config, depth + 1);
+        if !matches!(res, Err(ParseError::UnrecognisedCommand { name: None, .. })) {
+            return res;
+        }
+    }
+
+    path_command(&commands, rest, config, group)
+}
+
+fn path_command(
+    map: &CommandMap,
+    path: &[&str],
+    config: &Configuration,
+    group: &'static CommandGroup,
+) -> Result<Invoke, ParseError> {
+    match path_cmd(map, path, config, 0) {
+        Ok(command) => Ok(Invoke::Command {
+            group,
+            command,
+        }),
+        Err(err) => match group.options.default_command {
+            Some(command) => Ok(Invoke::Command {
+                group,
+                command,
+            }),
+            None => Err(err),
+        },
+    }
+}
+
+fn path_cmd(
+    map: &CommandMap,
+    path: &[&str],
+    config: &Configuration,
+    depth: usize,
+) -> Result<&'static Command, ParseError> {
+    let Some((name, rest)) = path.split_first() else {
+        return Err(ParseError::UnrecognisedCommand {
+            name: None,
+            remainder: String::new(),
+        });
+    };
+    let n = fold_command(config, name).into_owned();
+
+    if config.disabled_commands.iter().any(|disabled| fold_command(config, disabled) == n) {
+        return Err(ParseError::Dispatch {
+            error: DispatchError::CommandDisabled(n.clone()),
+            command_name: n,
+            remainder: rest.join(" "),
+        });
+    }
+
+    let Some((cmd, sub_commands)) = map.get(&n) else {
+        return Err(ParseError::UnrecognisedCommand {
+            name: Some(n),
+            remainder: path.join(" "),
+        });
+    };
+
+    if sub_commands.is_empty() || rest.is_empty() {
+        return Ok(cmd);
+    }
+
+    if depth >= config.max_command_depth {
+        return Err(ParseError::UnrecognisedCommand {
+            name: None,
+            remainder: rest.join(" "),
+        });
+    }
+
+    match path_cmd(&sub_commands, rest, config, depth + 1) {
+        Err(ParseError::UnrecognisedCommand {
+            name: Some(_), ..
+        }) => Ok(cmd),
+        res => res,
+    }
+}
+
+/// Help commands scoped to a group, as pairs of the group and the names of its help.
+pub type GroupHelps<'a> = &'a [(&'static CommandGroup, &'static [&'static str])];
+
+/// Consumes one of the help names from the stream, if it starts with any.
+fn help(
+    stream: &mut Stream<'_>,
+    config: &Configuration,
+    help_was_set: Option<&[&'static str]>,
+) -> Option<&'static str> {
+    for name in help_was_set? {
+        if let Some(raw) =
+            peek_folded(stream, &fold_command(config, name), |s| fold_command(config, s))
+        {
+            stream.increment(raw.len());
+
+            stream.take_while_char(config.whitespace);
+
+            return Some(name);
+        }
+    }
+
+    None
+}
+
+/// Consumes one of the names of the help scoped to the innermost group in `path` that has one.
+fn group_help(
+    stream: &mut Stream<'_>,
+    config: &Configuration,
+    path: &[&'static CommandGroup],
+    group_helps: GroupHelps<'_>,
+) -> Option<Invoke> {
+    path.iter().rev().find_map(|&group| {
+        let (_, names) = group_helps.iter().find(|(g, _)| std::ptr::eq(*g, group))?;
+
+        help(stream, config, Some(names)).map(|name| Invoke::Help {
+            name,
+            group: Some(group),
+        })
+    })
+}
+
 #[derive(Debug)]
 pub enum Invoke {
     Command { group: &'static CommandGroup, command: &'static Command },
-    Help(&'static str),
+    /// The global help, or the help scoped to `group`.
+    Help { name: &'static str, group: Option<&'static CommandGroup> },
+}
+
+/// What a help command was asked about, as resolved by [`resolve_help_target`].
+#[derive(Clone, Copy, Debug)]
+pub enum HelpTarget {
+    /// A command or sub-command, along with the group it belongs to.
+    Command { group: &'static CommandGroup, command: &'static Command },
+    /// A group, given by one of its prefixes or its name.
+    Group(&'static CommandGroup),
+}
+
+/// Resolves what a help command was asked about from the rest of the message after the help name,
+/// such as `kick` or `admin kick` in `~help admin kick`.
+///
+/// Groups are given by one of their prefixes or their name, after which their own commands and
+/// sub-groups are looked up. Commands are found in any group without giving its prefix, and are
+/// followed by the names of their sub-commands, if any. When a group is given but nothing after
+/// it matches, the group itself is returned.
+///
+/// Returns [`None`] if nothing was asked about, or if nothing matches.
+#[must_use]
+pub fn resolve_help_target(
+    groups: &[&'static CommandGroup],
+    rest: &str,
+    config: &Configuration,
+) -> Option<HelpTarget> {
+    let words = rest.split(config.whitespace).filter(|word| !word.is_empty()).collect::<Vec<_>>();
+
+    help_target_in_groups(groups, &words, config)
+}
+
+fn is_named(config: &Configuration, names: &[&str], word: &str) -> bool {
+    let word = fold_command(config, word);
+
+    names.iter().any(|name| fold_command(config, name) == word)
+}
+
+fn help_target_in_groups(
+    groups: &[&'static CommandGroup],
+    words: &[&str],
+    config: &Configuration,
+) -> Option<HelpTarget> {
+    let (word, rest) = words.split_first()?;
+
+    groups.iter().find_map(|&group| {
+        if is_named(config, group.options.prefixes, word) || is_named(config, &[group.name], word) {
+            Some(help_target_in_group(group, rest, config).unwrap_or(HelpTarget::Group(group)))
+        } else {
+            help_target_in_group(group, words, config)
+        }
+    })
+}
+
+fn help_target_in_group(
+    group: &'static CommandGroup,
+    words: &[&str],
+    config: &Configuration,
+) -> Option<HelpTarget> {
+    let (word, rest) = words.split_first()?;
+
+    let command = group.options.commands.iter().find(|c| is_named(config, c.options.names, word));
+    match command.copied() {
+        Some(command) => Some(HelpTarget::Command {
+            group,
+            command: sub_command(command, rest, config),
+        }),
+        None => help_target_in_groups(group.options.sub_groups, words, config),
+    }
+}
+
+/// Returns the innermost sub-command of `command` named by the words, in order.
+fn sub_command(
+    command: &'static Command,
+    words: &[&str],
+    config: &Configuration,
+) -> &'static Command {
+    let Some((word, rest)) = words.split_first() else { return command };
+
+    let sub = command.options.sub_commands.iter().find(|c| is_named(config, c.options.names, word));
+    match sub.copied() {
+        Some(sub) => sub_command(sub, rest, config),
+        None => command,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::framework::standard::tests::{
+        context,
+        message,
+        ping,
+        COMMAND_OPTIONS,
+        GENERAL_GROUP,
+        GROUP_OPTIONS,
+        PING_COMMAND,
+    };
+
+    #[test]
+    fn checkpoints() {
+        let mut stream = Stream::new("<@1> ping pong");
+        let outer = Checkpoint::new(&stream);
+
+        stream.increment(5);
+        let inner = Checkpoint::new(&stream);
+        stream.increment(5);
+        assert_eq!(stream.rest(), "pong");
+
+        inner.restore(&mut stream);
+        assert_eq!(stream.rest(), "ping pong");
+
+        // Restoring does not consume the checkpoint, and an outer one can be restored past an
+        // inner one.
+        stream.increment(5);
+        inner.restore(&mut stream);
+        assert_eq!(stream.rest(), "ping pong");
+
+        outer.restore(&mut stream);
+        assert_eq!(stream.rest(), "<@1> ping pong");
+        assert_eq!(Checkpoint::new(&stream), outer);
+    }
+
+    #[test]
+    fn role_mention_prefix() {
+        let mut config = Configuration::default();
+        config.on_mention(Some(UserId::new(1))).on_role_mention(Some(RoleId::new(2)));
+
+        let mut stream = Stream::new("<@&2> ping");
+        assert_eq!(role_mention(&mut stream, &config), Some("2"));
+        assert_eq!(stream.rest(), " ping");
+
+        let mut stream = Stream::new("<@&3> ping");
+        assert_eq!(role_mention(&mut stream, &config), None);
+        assert_eq!(stream.rest(), "<@&3> ping");
+
+        let mut stream = Stream::new("<@1> ping");
+        assert_eq!(role_mention(&mut stream, &config), None);
+        assert_eq!(mention(&mut stream, &config), MentionResult::Ours("1"));
+        assert_eq!(stream.rest(), " ping");
+    }
+
+    #[test]
+    fn mention_any_id() {
+        let mut config = Configuration::default();
+        config.on_mentions([UserId::new(1), UserId::new(2)]);
+
+        let mut stream = Stream::new("<@1> ping");
+        assert_eq!(mention(&mut stream, &config), MentionResult::Ours("1"));
+        assert_eq!(stream.rest(), " ping");
+
+        let mut stream = Stream::new("<@!2> ping");
+        assert_eq!(mention(&mut stream, &config), MentionResult::Ours("2"));
+        assert_eq!(stream.rest(), " ping");
+
+        let mut stream = Stream::new("<@3> ping");
+        assert_eq!(mention(&mut stream, &config), MentionResult::OtherBot("3"));
+        assert_eq!(stream.rest(), "<@3> ping");
+    }
+
+    #[test]
+    fn mention_results() {
+        let mut config = Configuration::default();
+
+        // Mentions of other users are told apart even when the bot answers to no mention.
+        let mut stream = Stream::new("<@!1> ping");
+        assert_eq!(mention(&mut stream, &config), MentionResult::OtherBot("1"));
+        assert_eq!(stream.rest(), "<@!1> ping");
+
+        config.on_mention(Some(UserId::new(1)));
+        let mut stream = Stream::new("<@!1> ping");
+        assert_eq!(mention(&mut stream, &config), MentionResult::Ours("1"));
+        assert_eq!(stream.rest(), " ping");
+
+        for content in ["~ping", "<@1 ping", "<@> ping", "<@&1> ping"] {
+            let mut stream = Stream::new(content);
+            assert_eq!(mention(&mut stream, &config), MentionResult::None, "{content}");
+            assert_eq!(stream.rest(), content);
+        }
+    }
+
+    #[tokio::test]
+    async fn no_dm_prefix() {
+        let mut config = Configuration::default();
+        config.no_dm_prefix(true);
+
+        let dm = message("ping");
+        let mut stream = Stream::new(&dm.content);
+        let prefix = super::prefix(&context(), &dm, &mut stream, &config).await;
+        assert_eq!(prefix.as_deref(), Some(""));
+        assert_eq!(stream.rest(), "ping");
+
+        let dm = message("~ping");
+        let mut stream = Stream::new(&dm.content);
+        let prefix = super::prefix(&context(), &dm, &mut stream, &config).await;
+        assert_eq!(prefix.as_deref(), Some("~"));
+        assert_eq!(stream.rest(), "ping");
+
+        let mut guild = message("ping");
+        guild.guild_id = Some(GuildId::new(1));
+        let mut stream = Stream::new(&guild.content);
+        assert_eq!(super::prefix(&context(), &guild, &mut stream, &config).await, None);
+
+        config.allow_dm(false);
+        let mut stream = Stream::new(&dm.content[1..]);
+        assert_eq!(super::prefix(&context(), &dm, &mut stream, &config).await, None);
+    }
+
+    fn skip_tag(content: &str) -> usize {
+        match content.find("] ") {
+            Some(end) if content.starts_with('[') => end + 2,
+            _ => 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn leading_skip() {
+        let mut config = Configuration::default();
+        config.leading_skip(skip_tag);
+
+        let msg = message("[relay] ~ping");
+        let content = super::leading_skip(&msg.content, &config).unwrap();
+        assert_eq!(content, "~ping");
+
+        let mut stream = Stream::new(content);
+        assert!(super::prefix(&context(), &msg, &mut stream, &config).await.is_some());
+        let res =
+            command(&context(), &msg, &mut stream, &groups(&config), &config, None, &[]).await;
+        match res {
+            Ok(Invoke::Command {
+                command, ..
+            }) => assert_eq!(command.options.names, ["ping"]),
+            _ => panic!("expected the command after the tag"),
+        }
+
+        assert_eq!(super::leading_skip("~ping", &config), Some("~ping"));
+
+        config.leading_skip(|_| 3);
+        assert_eq!(super::leading_skip("~ping", &config), Some("ng"));
+        assert_eq!(super::leading_skip("~", &config), None);
+        assert_eq!(super::leading_skip("~p\u{ef}ng", &config), None);
+    }
+
+    #[tokio::test]
+    async fn longest_prefix() {
+        let mut config = Configuration::default();
+        config.prefixes(["!", "!!"]);
+
+        let msg = message("!!ping");
+        let mut stream = Stream::new(&msg.content);
+        let prefix = super::prefix(&context(), &msg, &mut stream, &config).await;
+        assert_eq!(prefix.as_deref(), Some("!!"));
+        assert_eq!(stream.rest(), "ping");
+
+        let msg = message("!ping");
+        let mut stream = Stream::new(&msg.content);
+        let prefix = super::prefix(&context(), &msg, &mut stream, &config).await;
+        assert_eq!(prefix.as_deref(), Some("!"));
+        assert_eq!(stream.rest(), "ping");
+
+        // A longer static prefix wins over a shorter dynamic one.
+        let mut config = Configuration::default();
+        config.prefix("!!").dynamic_prefix(|_, _| async { Some("!".to_string()) }.boxed());
+
+        let msg = message("!!ping");
+        let mut stream = Stream::new(&msg.content);
+        let prefix = super::prefix(&context(), &msg, &mut stream, &config).await;
+        assert_eq!(prefix.as_deref(), Some("!!"));
+        assert_eq!(stream.rest(), "ping");
+    }
+
+    #[tokio::test]
+    async fn prefix_touches_command() {
+        let mut config = Configuration::default();
+        config.prefix("!").with_whitespace(true).require_prefix_touches_command(true);
+
+        let msg = message("!ping");
+        let mut stream = Stream::new(&msg.content);
+        let prefix = super::prefix(&context(), &msg, &mut stream, &config).await;
+        assert_eq!(prefix.as_deref(), Some("!"));
+        assert_eq!(stream.rest(), "ping");
+
+        let msg = message("! ping");
+        let mut stream = Stream::new(&msg.content);
+        assert_eq!(super::prefix(&context(), &msg, &mut stream, &config).await, None);
+
+        config.require_prefix_touches_command(false);
+        let mut stream = Stream::new(&msg.content);
+        let prefix = super::prefix(&context(), &msg, &mut stream, &config).await;
+        assert_eq!(prefix.as_deref(), Some("!"));
+        assert_eq!(stream.rest(), "ping");
+    }
+
+    #[cfg(feature = "cache")]
+    static DYNAMIC_PREFIX_CALLS: std::sync::atomic::AtomicUsize =
+        std::sync::atomic::AtomicUsize::new(0);
+
+    #[cfg(feature = "cache")]
+    fn counted_prefix<'fut>(_: &'fut Context, _: &'fut Message) -> BoxFuture<'fut, Option<String>> {
+        DYNAMIC_PREFIX_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+        async { Some("!".to_string()) }.boxed()
+    }
+
+    #[cfg(feature = "cache")]
+    #[tokio::test]
+    async fn cached_dynamic_prefix() {
+        use std::sync::atomic::Ordering;
+        use std::time::Duration;
+
+        let cache = PrefixCache::new(Duration::from_millis(200));
+        let mut config = Configuration::default();
+        config.dynamic_prefix(counted_prefix).prefix_cache(cache.clone());
+
+        let mut msg = message("!ping");
+        msg.guild_id = Some(GuildId::new(1));
+        let parse = |msg: &Message| {
+            let (ctx, msg, config) = (context(), msg.clone(), config.clone());
+            async move {
+                let mut stream = Stream::new(&msg.content);
+                super::prefix(&ctx, &msg, &mut stream, &config).await.map(Cow::into_owned)
+            }
+        };
+
+        assert_eq!(parse(&msg).await.as_deref(), Some("!"));
+        assert_eq!(parse(&msg).await.as_deref(), Some("!"));
+        assert_eq!(DYNAMIC_PREFIX_CALLS.load(Ordering::SeqCst), 1);
+
+        cache.invalidate(GuildId::new(1));
+        assert_eq!(parse(&msg).await.as_deref(), Some("!"));
+        assert_eq!(DYNAMIC_PREFIX_CALLS.load(Ordering::SeqCst), 2);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(parse(&msg).await.as_deref(), Some("!"));
+        assert_eq!(parse(&msg).await.as_deref(), Some("!"));
+        assert_eq!(DYNAMIC_PREFIX_CALLS.load(Ordering::SeqCst), 3);
+
+        // Messages outside of guilds always run the hooks.
+        msg.guild_id = None;
+        assert_eq!(parse(&msg).await.as_deref(), Some("!"));
+        assert_eq!(DYNAMIC_PREFIX_CALLS.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn role_mention_clears_whitespace() {
+        let mut config = Configuration::default();
+        config.on_role_mention(Some(RoleId::new(2)));
+        let msg = message("<@&2>   ping");
+        let mut stream = Stream::new(&msg.content);
+
+        let prefix = super::prefix(&context(), &msg, &mut stream, &config).await;
+
+        assert_eq!(prefix.as_deref(), Some("2"));
+        assert_eq!(stream.rest(), "ping");
+    }
+
+    #[tokio::test]
+    async fn quoted_command_name() {
+        let mut config = Configuration::default();
+        config.quoted_commands(true);
+        let msg = message("\"say hello\" world");
+        let mut stream = Stream::new(&msg.content);
+
+        let res =
+            command(&context(), &msg, &mut stream, &groups(&config), &config, None, &[]).await;
+
+        match res {
+            Ok(Invoke::Command {
+                command, ..
+            }) => assert_eq!(command.options.names, ["say hello"]),
+            _ => panic!("expected the quoted command"),
+        }
+        assert_eq!(stream.rest(), "world");
+
+        // Without quoting, the name is only found when not splitting by whitespace.
+        config.by_space(false);
+        let msg = message("say hello world");
+        let mut stream = Stream::new(&msg.content);
+
+        let res =
+            command(&context(), &msg, &mut stream, &groups(&config), &config, None, &[]).await;
+
+        match res {
+            Ok(Invoke::Command {
+                command, ..
+            }) => assert_eq!(command.options.names, ["say hello"]),
+            _ => panic!("expected the unquoted command"),
+        }
+        assert_eq!(stream.rest(), "world");
+    }
+
+    async fn is_disabled(config: &Configuration, content: &str) -> bool {
+        let msg = message(content);
+        let mut stream = Stream::new(&msg.content);
+
+        let res = command(&context(), &msg, &mut stream, &groups(config), config, None, &[]).await;
+
+        matches!(res, Err(ParseError::Dispatch {
+            error: DispatchError::CommandDisabled(_),
+            ..
+        }))
+    }
+
+    #[tokio::test]
+    async fn disabled_commands_case() {
+        let mut config = Configuration::default();
+        config.disabled_commands(HashSet::from(["PiNg".to_string()]));
+
+        assert!(!is_disabled(&config, "ping").await);
+        assert!(is_disabled(&config, "PiNg").await);
+
+        config.case_insensitivity(true);
+
+        assert!(is_disabled(&config, "ping").await);
+        assert!(is_disabled(&config, "PING").await);
+    }
+
+    async fn parses(config: &Configuration, content: &str) -> bool {
+        let msg = message(content);
+        let mut stream = Stream::new(&msg.content);
+
+        if super::prefix(&context(), &msg, &mut stream, config).await.is_none() {
+            return false;
+        }
+
+        let res = command(&context(), &msg, &mut stream, &groups(config), config, None, &[]).await;
+        matches!(res, Ok(Invoke::Command { .. }))
+    }
+
+    #[tokio::test]
+    async fn case_insensitive_prefixes_and_commands() {
+        let mut config = Configuration::default();
+        config.prefix("Bot");
+
+        for (prefixes, commands) in [(false, false), (true, false), (false, true), (true, true)] {
+            config.case_insensitive_prefixes(prefixes).case_insensitive_commands(commands);
+
+            assert!(parses(&config, "Botping").await);
+            assert_eq!(parses(&config, "botping").await, prefixes);
+            assert_eq!(parses(&config, "BotPING").await, commands);
+            assert_eq!(parses(&config, "botPING").await, prefixes && commands);
+        }
+
+        config.case_insensitivity(false);
+        assert!(!config.case_insensitive_prefixes && !config.case_insensitive_commands);
+        assert!(!parses(&config, "botping").await);
+    }
+
+    static STRASSE_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["stra\u{df}e"],
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    static STRASSE_GROUP: CommandGroup = CommandGroup {
+        name: "Strasse",
+        options: &GroupOptions {
+            commands: &[&STRASSE_COMMAND],
+            ..GROUP_OPTIONS
+        },
+    };
+
+    async fn parses_strasse(config: &Configuration, content: &str) -> bool {
+        let msg = message(content);
+        let mut stream = Stream::new(&msg.content);
+
+        let options = STRASSE_GROUP.options;
+        let map = Map::Prefixless(
+            GroupMap::new(options.sub_groups, config),
+            CommandMap::new(options.commands, config),
+        );
+        let groups = [(&STRASSE_GROUP, map)];
+
+        let res = command(&context(), &msg, &mut stream, &groups, config, None, &[]).await;
+        matches!(res, Ok(Invoke::Command { .. })) && stream.is_empty()
+    }
+
+    #[tokio::test]
+    async fn sharp_s_prefixes_and_commands() {
+        let modes = [
+            (CaseFolding::Ascii, false),
+            (CaseFolding::Lowercase, false),
+            (CaseFolding::Unicode, true),
+        ];
+
+        for (folding, folds_to_ss) in modes {
+            let mut config = Configuration::default();
+            config.prefix("stra\u{df}e").case_insensitivity(true).case_folding(folding);
+
+            for (content, matches) in [
+                ("STRA\u{df}Eping", true),
+                ("stra\u{df}eping", true),
+                ("STRASSEping", folds_to_ss),
+                ("strasseping", folds_to_ss),
+            ] {
+                let msg = message(content);
+                let mut stream = Stream::new(&msg.content);
+                let prefix = super::prefix(&context(), &msg, &mut stream, &config).await;
+                assert_eq!(prefix.is_some(), matches, "{folding:?} prefix in {content:?}");
+                if matches {
+                    assert_eq!(stream.rest(), "ping");
+                }
+            }
+
+            for (content, matches) in
+                [("STRA\u{df}E", true), ("STRASSE", folds_to_ss), ("strasse", folds_to_ss)]
+            {
+                let res = parses_strasse(&config, content).await;
+                assert_eq!(res, matches, "{folding:?} command {content:?}");
+
+                let mut stream = Stream::new(content);
+                let help = super::help(&mut stream, &config, Some(&["stra\u{df}e"]));
+                assert_eq!(help.is_some(), matches, "{folding:?} help {content:?}");
+            }
+        }
+    }
+
+    #[cfg(feature = "cache")]
+    async fn check_timed_out(
+        config: &Configuration,
+        until: Option<Timestamp>,
+    ) -> Result<(), DispatchError> {
+        let guild_id = GuildId::new(1);
+        let mut msg = message("ping");
+        msg.author.id = UserId::new(2);
+        msg.guild_id = Some(guild_id);
+
+        let mut guild = Guild {
+            id: guild_id,
+            ..Default::default()
+        };
+        guild.members.insert(msg.author.id, Member {
+            user: msg.author.clone(),
+            communication_disabled_until: until,
+            guild_id,
+            ..Default::default()
+        });
+
+        let mut ctx = context();
+        ctx.cache = std::sync::Arc::new(crate::cache::Cache::default());
+        ctx.cache.guilds.insert(guild_id, guild);
+
+        check_discrepancy(&ctx, &msg, config, &&COMMAND_OPTIONS).await
+    }
+
+    #[cfg(feature = "cache")]
+    #[tokio::test]
+    async fn timed_out_members() {
+        let now = Timestamp::now().unix_timestamp();
+        let future = Some(Timestamp::from_unix_timestamp(now + 600).unwrap());
+        let past = Some(Timestamp::from_unix_timestamp(now - 600).unwrap());
+
+        let mut config = Configuration::default();
+        assert!(check_timed_out(&config, future).await.is_ok());
+
+        config.ignore_timed_out_members(true);
+        let res = check_timed_out(&config, future).await;
+        assert!(matches!(res, Err(DispatchError::UserTimedOut)));
+        assert!(check_timed_out(&config, past).await.is_ok());
+        assert!(check_timed_out(&config, None).await.is_ok());
+    }
+
+    #[cfg(feature = "cache")]
+    async fn check_bot_permissions(bot_roles: Vec<RoleId>) -> Result<(), DispatchError> {
+        let guild_id = GuildId::new(1);
+        let bot_id = UserId::new(3);
+        let mut msg = message("kick");
+        msg.author.id = UserId::new(2);
+        msg.guild_id = Some(guild_id);
+
+        let role = |id, permissions| Role {
+            id: RoleId::new(id),
+            guild_id,
+            permissions,
+            ..Default::default()
+        };
+        let mut guild = Guild {
+            id: guild_id,
+            owner_id: msg.author.id,
+            ..Default::default()
+        };
+        guild.roles.insert(RoleId::new(1), role(1, Permissions::SEND_MESSAGES));
+        guild.roles.insert(RoleId::new(4), role(4, Permissions::KICK_MEMBERS));
+        for (user_id, roles) in [(msg.author.id, vec![]), (bot_id, bot_roles)] {
+            guild.members.insert(user_id, Member {
+                user: User {
+                    id: user_id,
+                    ..Default::default()
+                },
+                roles,
+                guild_id,
+                ..Default::default()
+            });
+        }
+
+        let mut ctx = context();
+        ctx.cache = std::sync::Arc::new(crate::cache::Cache::default());
+        ctx.cache.user.write().id = bot_id;
+        ctx.cache.guilds.insert(guild_id, guild);
+
+        let options = CommandOptions {
+            bot_required_permissions: Permissions::KICK_MEMBERS,
+            ..COMMAND_OPTIONS
+        };
+        check_discrepancy(&ctx, &msg, &Configuration::default(), &&options).await
+    }
+
+    #[cfg(feature = "cache")]
+    #[tokio::test]
+    async fn bot_lacking_permissions() {
+        // The author owns the guild, but that does not grant the bot any permissions.
+        let res = check_bot_permissions(vec![]).await;
+        assert!(matches!(
+            res,
+            Err(DispatchError::BotLackingPermissions(Permissions::KICK_MEMBERS))
+        ));
+
+        assert!(check_bot_permissions(vec![RoleId::new(4)]).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn permissions_in_dm() {
+        let options = CommandOptions {
+            required_permissions: Permissions::ADMINISTRATOR,
+            ..COMMAND_OPTIONS
+        };
+        let mut config = Configuration::default();
+        let msg = message("ping");
+
+        let res = check_discrepancy(&context(), &msg, &config, &&options).await;
+        assert!(matches!(
+            res,
+            Err(DispatchError::PermissionsUnavailableInDM(Permissions::ADMINISTRATOR))
+        ));
+
+        // Owners keep their privilege, and ungated commands are unaffected.
+        config.owners(HashSet::from([msg.author.id]));
+        assert!(check_discrepancy(&context(), &msg, &config, &&options).await.is_ok());
+
+        let config = Configuration::default();
+        assert!(check_discrepancy(&context(), &msg, &config, &&COMMAND_OPTIONS).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn command_access() {
+        let owner = UserId::new(1);
+        let mut config = Configuration::default();
+        config.owners(HashSet::from([owner]));
+
+        let mut access = AccessContext {
+            user_id: UserId::new(2),
+            channel_id: ChannelId::new(3),
+            guild_id: None,
+        };
+        let owners_only = CommandOptions {
+            owners_only: true,
+            ..COMMAND_OPTIONS
+        };
+        let res = check_command_access(&context(), access, &config, &&owners_only).await;
+        assert!(matches!(res, Err(DispatchError::OnlyForOwners)));
+
+        let gated = CommandOptions {
+            required_permissions: Permissions::BAN_MEMBERS,
+            ..COMMAND_OPTIONS
+        };
+        let res = check_command_access(&context(), access, &config, &&gated).await;
+        assert!(matches!(
+            res,
+            Err(DispatchError::PermissionsUnavailableInDM(Permissions::BAN_MEMBERS))
+        ));
+
+        access.user_id = owner;
+        assert!(check_command_access(&context(), access, &config, &&owners_only).await.is_ok());
+        assert!(check_command_access(&context(), access, &config, &&gated).await.is_ok());
+
+        // Messages are checked the same way, through their author and channel.
+        let mut msg = message("ping");
+        msg.author.id = owner;
+        let access = AccessContext::from(&msg);
+        assert_eq!((access.user_id, access.guild_id), (owner, None));
+        assert!(check_discrepancy(&context(), &msg, &config, &&owners_only).await.is_ok());
+    }
+
+    fn groups(config: &Configuration) -> Vec<(&'static CommandGroup, Map)> {
+        let options = GENERAL_GROUP.options;
+        let map = Map::Prefixless(
+            GroupMap::new(options.sub_groups, config),
+            CommandMap::new(options.commands, config),
+        );
+
+        vec![(&GENERAL_GROUP, map)]
+    }
+
+    static HELP_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["help"],
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    static HELP_GROUP: CommandGroup = CommandGroup {
+        name: "Help",
+        options: &GroupOptions {
+            commands: &[&HELP_COMMAND],
+            ..GROUP_OPTIONS
+        },
+    };
+
+    async fn invoke(config: &Configuration, content: &str) -> Invoke {
+        let options = HELP_GROUP.options;
+        let map = Map::Prefixless(
+            GroupMap::new(options.sub_groups, config),
+            CommandMap::new(options.commands, config),
+        );
+        let groups = [(&HELP_GROUP, map)];
+        let msg = message(content);
+        let mut stream = Stream::new(&msg.content);
+
+        let help_was_set = Some(&["help", "h"][..]);
+        command(&context(), &msg, &mut stream, &groups, config, help_was_set, &[]).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn help_precedence() {
+        let mut config = Configuration::default();
+
+        let help = invoke(&config, "help").await;
+        assert!(matches!(help, Invoke::Help { name: "help", group: None }));
+        assert!(matches!(invoke(&config, "h").await, Invoke::Help { name: "h", group: None }));
+
+        config.help_takes_precedence(false);
+
+        let Invoke::Command {
+            command, ..
+        } = invoke(&config, "help").await
+        else {
+            panic!("expected the command named help");
+        };
+        assert!(std::ptr::eq(command, &HELP_COMMAND));
+        assert!(matches!(invoke(&config, "h").await, Invoke::Help { name: "h", group: None }));
+    }
+
+    static DICE_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["dice"],
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    static GAMES_GROUP: CommandGroup = CommandGroup {
+        name: "Games",
+        options: &GroupOptions {
+            prefixes: &["fun"],
+            commands: &[&DICE_COMMAND],
+            ..GROUP_OPTIONS
+        },
+    };
+
+    static OUTER_GROUP: CommandGroup = CommandGroup {
+        name: "Outer",
+        options: &GroupOptions {
+            sub_groups: &[&GAMES_GROUP],
+            ..GROUP_OPTIONS
+        },
+    };
+
+    async fn invoke_scoped(content: &str) -> Result<Invoke, ParseError> {
+        let config = Configuration::default();
+        let groups = [(&OUTER_GROUP, Map::WithPrefixes(GroupMap::new(&[&GAMES_GROUP], &config)))];
+        let msg = message(content);
+        let mut stream = Stream::new(&msg.content);
+
+        let group_helps: GroupHelps<'_> = &[(&GAMES_GROUP, &["help", "games"])];
+        command(&context(), &msg, &mut stream, &groups, &config, Some(&["help"]), group_helps).await
+    }
+
+    #[tokio::test]
+    async fn group_help_precedence() {
+        let res = invoke_scoped("help").await;
+        assert!(matches!(res, Ok(Invoke::Help { name: "help", group: None })));
+
+        let Ok(Invoke::Help {
+            name,
+            group: Some(group),
+        }) = invoke_scoped("fun help").await
+        else {
+            panic!("expected the help scoped to the group");
+        };
+        assert_eq!(name, "help");
+        assert!(std::ptr::eq(group, &GAMES_GROUP));
+
+        let res = invoke_scoped("fun games").await;
+        assert!(matches!(res, Ok(Invoke::Help { name: "games", group: Some(_) })));
+        assert!(matches!(invoke_scoped("fun dice").await, Ok(Invoke::Command { .. })));
+
+        // The scoped help is not invoked outside of the prefix of its group.
+        assert!(is_unrecognised(&invoke_scoped("games").await));
+    }
+
+    static KICK_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["kick"],
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    static MODERATION_GROUP: CommandGroup = CommandGroup {
+        name: "Moderation",
+        options: &GroupOptions {
+            prefixes: &["admin"],
+            commands: &[&KICK_COMMAND],
+            ..GROUP_OPTIONS
+        },
+    };
+
+    #[test]
+    fn help_targets() {
+        let config = Configuration::default();
+        let groups = [&GENERAL_GROUP, &MODERATION_GROUP];
+        let resolve = |rest| resolve_help_target(&groups, rest, &config);
+
+        for rest in ["kick", "admin kick", "  admin   kick "] {
+            let Some(HelpTarget::Command {
+                group,
+                command,
+            }) = resolve(rest)
+            else {
+                panic!("expected the kick command for {rest:?}");
+            };
+            assert!(std::ptr::eq(group, &MODERATION_GROUP));
+            assert!(std::ptr::eq(command, &KICK_COMMAND));
+        }
+
+        for rest in ["admin", "Moderation", "admin unknown"] {
+            let Some(HelpTarget::Group(group)) = resolve(rest) else {
+                panic!("expected the moderation group for {rest:?}");
+            };
+            assert!(std::ptr::eq(group, &MODERATION_GROUP));
+        }
+
+        assert!(matches!(resolve("ping"), Some(HelpTarget::Command { .. })));
+        assert!(resolve("unknown").is_none());
+        assert!(resolve("").is_none());
+    }
+
+    #[tokio::test]
+    async fn command_paths() {
+        let config = Configuration::default();
+        let groups = [&GENERAL_GROUP, &MODERATION_GROUP].map(|group| {
+            let map = if group.options.prefixes.is_empty() {
+                Map::Prefixless(
+                    GroupMap::new(group.options.sub_groups, &config),
+                    CommandMap::new(group.options.commands, &config),
+                )
+            } else {
+                Map::WithPrefixes(GroupMap::new(&[group], &config))
+            };
+            (group, map)
+        });
+
+        let msg = message("admin kick");
+        let mut stream = Stream::new(&msg.content);
+        let Ok(Invoke::Command {
+            group: text_group,
+            command: text_command,
+        }) = command(&context(), &msg, &mut stream, &groups, &config, None, &[]).await
+        else {
+            panic!("expected the kick command from text");
+        };
+
+        let Ok(Invoke::Command {
+            group,
+            command,
+        }) = command_path(&groups, &["admin", "kick"], &config)
+        else {
+            panic!("expected the kick command from the path");
+        };
+        assert!(std::ptr::eq(group, text_group));
+        assert!(std::ptr::eq(command, text_command));
+        assert!(std::ptr::eq(command, &KICK_COMMAND));
+
+        let res = command_path(&groups, &["ping"], &config);
+        assert!(matches!(res, Ok(Invoke::Command { command, .. }) if command == &PING_COMMAND));
+        assert!(is_unrecognised(&command_path(&groups, &["kick"], &config)));
+        assert!(is_unrecognised(&command_path(&groups, &["admin", "unknown"], &config)));
+        assert!(is_unrecognised(&command_path(&groups, &[], &config)));
+    }
+
+    static FUN_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["fun"],
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    static FUN_GROUP: CommandGroup = CommandGroup {
+        name: "Fun",
+        options: &GroupOptions {
+            commands: &[&FUN_COMMAND],
+            ..GROUP_OPTIONS
+        },
+    };
+
+    #[tokio::test]
+    async fn prefixless_groups_restart() {
+        let config = Configuration::default();
+        let groups = [&OUTER_GROUP, &FUN_GROUP].map(|group| {
+            let map = Map::Prefixless(
+                GroupMap::new(group.options.sub_groups, &config),
+                CommandMap::new(group.options.commands, &config),
+            );
+            (group, map)
+        });
+        let msg = message("fun stuff");
+        let mut stream = Stream::new(&msg.content);
+
+        let res = command(&context(), &msg, &mut stream, &groups, &config, None, &[]).await;
+
+        // The sub-group prefix of the first group matches, but none of its commands do.
+        let Ok(Invoke::Command {
+            command, ..
+        }) = res
+        else {
+            panic!("expected the command of the second group");
+        };
+        assert!(std::ptr::eq(command, &FUN_COMMAND));
+        assert_eq!(stream.rest(), "stuff");
+    }
+
+    static INNER_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["inner"],
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    static MIDDLE_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["middle"],
+            sub_commands: &[&INNER_COMMAND],
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    static OUTER_COMMAND: Command = Command {
+        fun: ping,
+        options: &CommandOptions {
+            names: &["outer"],
+            sub_commands: &[&MIDDLE_COMMAND],
+            ..COMMAND_OPTIONS
+        },
+    };
+
+    static NESTED_GROUP: CommandGroup = CommandGroup {
+        name: "Nested",
+        options: &GroupOptions {
+            commands: &[&OUTER_COMMAND],
+            ..GROUP_OPTIONS
+        },
+    };
+
+    async fn nested(config: &Configuration, content: &str) -> Result<Invoke, ParseError> {
+        let options = NESTED_GROUP.options;
+        let map = Map::Prefixless(
+            GroupMap::new(options.sub_groups, config),
+            CommandMap::new(options.commands, config),
+        );
+        let groups = [(&NESTED_GROUP, map)];
+        let msg = message(content);
+        let mut stream = Stream::new(&msg.content);
+
+        command(&context(), &msg, &mut stream, &groups, config, None, &[]).await
+    }
+
+    #[tokio::test]
+    async fn max_command_depth() {
+        let mut config = Configuration::default();
+        config.max_command_depth(2);
+
+        let Ok(Invoke::Command {
+            command, ..
+        }) = nested(&config, "outer middle inner").await
+        else {
+            panic!("expected the innermost command");
+        };
+        assert!(std::ptr::eq(command, &INNER_COMMAND));
+
+        config.max_command_depth(1);
+
+        let res = nested(&config, "outer middle inner").await;
+        assert!(matches!(res, Err(ParseError::UnrecognisedCommand {
+            name: None,
+            ..
+        })));
+
+        // Reaching the limit is fine as long as the message names nothing deeper.
+        let Ok(Invoke::Command {
+            command, ..
+        }) = nested(&config, "outer middle").await
+        else {
+            panic!("expected the middle command");
+        };
+        assert!(std::ptr::eq(command, &MIDDLE_COMMAND));
+    }
+
+    #[tokio::test]
+    async fn unrecognised_command_remainder() {
+        let config = Configuration::default();
+        let msg = message("pong foo bar");
+        let mut stream = Stream::new(&msg.content);
+
+        let res =
+            command(&context(), &msg, &mut stream, &groups(&config), &config, None, &[]).await;
+
+        match res {
+            Err(ParseError::UnrecognisedCommand {
+                name,
+                remainder,
+            }) => {
+                assert_eq!(name.as_deref(), Some("pong"));
+                assert_eq!(remainder, "pong foo bar");
+            },
+            _ => panic!("expected an unrecognised command"),
+        }
+
+        let mut config = Configuration::default();
+        config.disabled_commands(HashSet::from(["ping".to_string()]));
+        let msg = message("ping foo bar");
+        let mut stream = Stream::new(&msg.content);
+
+        let res =
+            command(&context(), &msg, &mut stream, &groups(&config), &config, None, &[]).await;
+
+        match res {
+            Err(ParseError::Dispatch {
+                error: DispatchError::CommandDisabled(name),
+                command_name,
+                remainder,
+            }) => {
+                assert_eq!(name, "ping");
+                assert_eq!(command_name, "ping");
+                assert_eq!(remainder, "ping foo bar");
+            },
+            _ => panic!("expected a disabled command"),
+        }
+    }
+
+    /// The names and values of the fields recorded on a span.
+    type Fields = Vec<(&'static str, String)>;
+
+    /// Records the name and fields of every span, to inspect those emitted while parsing.
+    #[derive(Default)]
+    struct SpanRecorder {
+        spans: Arc<std::sync::Mutex<Vec<(&'static str, Fields)>>>,
+    }
+
+    struct FieldRecorder<'a>(&'a mut Fields);
+
+    impl tracing::field::Visit for FieldRecorder<'_> {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.push((field.name(), value.to_string()));
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.push((field.name(), format!("{value:?}")));
+        }
+    }
+
+    impl tracing::Subscriber for SpanRecorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut fields = Vec::new();
+            span.record(&mut FieldRecorder(&mut fields));
+
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((span.metadata().name(), fields));
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            let index = usize::try_from(span.into_u64()).unwrap() - 1;
+            values.record(&mut FieldRecorder(&mut spans[index].1));
+        }
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, _: &tracing::Event<'_>) {}
+
+        fn enter(&self, _: &tracing::span::Id) {}
+
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[tokio::test]
+    async fn command_span() {
+        let recorder = SpanRecorder::default();
+        let spans = Arc::clone(&recorder.spans);
+        let _guard = tracing::subscriber::set_default(recorder);
+
+        let config = Configuration::default();
+        let mut msg = message("ping secret");
+        msg.guild_id = Some(GuildId::new(1));
+        let mut stream = Stream::new(&msg.content);
+
+        let res =
+            command(&context(), &msg, &mut stream, &groups(&config), &config, None, &[]).await;
+        assert!(matches!(res, Ok(Invoke::Command { .. })));
+
+        let spans = spans.lock().unwrap();
+        let (_, fields) = spans.iter().find(|(name, _)| *name == "parse_command").unwrap();
+        assert!(fields.contains(&("command", "ping".to_string())));
+        assert!(fields.contains(&("guild_id", format!("{:?}", msg.guild_id))));
+        assert!(fields.contains(&("channel_id", msg.channel_id.to_string())));
+        assert!(fields.contains(&("author_id", msg.author.id.to_string())));
+        assert!(spans.iter().any(|(name, _)| *name == "check_discrepancy"));
+        assert!(spans.iter().flat_map(|(_, fields)| fields).all(|(_, v)| !v.contains("secret")));
+    }
 }
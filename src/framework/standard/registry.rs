@@ -0,0 +1,97 @@
+use super::{Command, CommandGroup, CommandOptions, GroupOptions};
+
+/// A read-only view of a group registered with a [`StandardFramework`], such as to document the
+/// available commands.
+///
+/// The options of the group and its commands implement [`CommonOptions`], exposing the
+/// permissions, roles and checks required to use them.
+///
+/// [`StandardFramework`]: super::StandardFramework
+/// [`CommonOptions`]: super::CommonOptions
+#[derive(Clone, Copy, Debug)]
+pub struct GroupView {
+    group: &'static CommandGroup,
+}
+
+impl GroupView {
+    pub(crate) fn new(group: &'static CommandGroup) -> Self {
+        Self {
+            group,
+        }
+    }
+
+    /// Returns the group this is a view of.
+    #[must_use]
+    pub fn group(&self) -> &'static CommandGroup {
+        self.group
+    }
+
+    /// Returns the name of the group.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.group.name
+    }
+
+    /// Returns the options of the group, such as its prefixes and description.
+    #[must_use]
+    pub fn options(&self) -> &'static GroupOptions {
+        self.group.options
+    }
+
+    /// Returns the command run when the group is invoked without a command, if any.
+    #[must_use]
+    pub fn default_command(&self) -> Option<CommandView> {
+        self.group.options.default_command.map(CommandView::new)
+    }
+
+    /// Returns the commands of the group, in the order they were declared.
+    pub fn commands(&self) -> impl Iterator<Item = CommandView> {
+        self.group.options.commands.iter().copied().map(CommandView::new)
+    }
+
+    /// Returns the groups nested in this group, in the order they were declared.
+    pub fn sub_groups(&self) -> impl Iterator<Item = GroupView> {
+        self.group.options.sub_groups.iter().copied().map(GroupView::new)
+    }
+}
+
+/// A read-only view of a command registered with a [`StandardFramework`], as part of a
+/// [`GroupView`].
+///
+/// [`StandardFramework`]: super::StandardFramework
+#[derive(Clone, Copy, Debug)]
+pub struct CommandView {
+    command: &'static Command,
+}
+
+impl CommandView {
+    fn new(command: &'static Command) -> Self {
+        Self {
+            command,
+        }
+    }
+
+    /// Returns the command this is a view of.
+    #[must_use]
+    pub fn command(&self) -> &'static Command {
+        self.command
+    }
+
+    /// Returns the names of the command, the first of which is its main name and the rest its
+    /// aliases.
+    #[must_use]
+    pub fn names(&self) -> &'static [&'static str] {
+        self.command.options.names
+    }
+
+    /// Returns the options of the command, such as its description and usage.
+    #[must_use]
+    pub fn options(&self) -> &'static CommandOptions {
+        self.command.options
+    }
+
+    /// Returns the sub-commands of the command, in the order they were declared.
+    pub fn sub_commands(&self) -> impl Iterator<Item = CommandView> {
+        self.command.options.sub_commands.iter().copied().map(CommandView::new)
+    }
+}
@@ -109,6 +109,7 @@ pub(crate) struct TicketCounter {
     pub check: Option<Check>,
     pub delay_action: Option<DelayHook>,
     pub await_ratelimits: u32,
+    pub limited_for: LimitedFor,
 }
 
 /// Contains information about a rate limit.
@@ -125,6 +126,8 @@ pub struct RateLimitInfo {
     pub is_first_try: bool,
     /// How the command invocation has been treated by the framework.
     pub action: RateLimitAction,
+    /// What the exceeded bucket collects tickets for.
+    pub limited_for: LimitedFor,
 }
 
 /// Action taken for the command invocation.
@@ -226,6 +229,7 @@ impl TicketCounter {
                         max_delays: self.await_ratelimits,
                         action,
                         is_first_try: was_first_try,
+                        limited_for: self.limited_for,
                     });
                 }
                 ticket_owner.tickets = 0;
@@ -269,6 +273,7 @@ impl TicketCounter {
                 max_delays: self.await_ratelimits,
                 action,
                 is_first_try: was_first_try,
+                limited_for: self.limited_for,
             });
         }
         ticket_owner.awaiting = ticket_owner.awaiting.saturating_sub(1);
@@ -319,7 +324,7 @@ impl fmt::Display for RevertBucket {
 impl std::error::Error for RevertBucket {}
 
 /// Decides what a bucket will use to collect tickets for.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum LimitedFor {
     /// The bucket will collect tickets for every invocation of a command.
     Global,
@@ -553,6 +558,7 @@ impl BucketBuilder {
             check: self.check,
             delay_action: self.delay_action,
             await_ratelimits: self.await_ratelimits,
+            limited_for: self.limited_for,
         };
 
         match self.limited_for {
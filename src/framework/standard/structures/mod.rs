@@ -58,6 +58,8 @@ pub struct CommandOptions {
     pub allowed_roles: &'static [&'static str],
     /// Permissions required to use this command.
     pub required_permissions: Permissions,
+    /// Permissions the bot itself requires to perform this command.
+    pub bot_required_permissions: Permissions,
     /// Whether the command should be displayed in help list or not, used by other commands.
     pub help_available: bool,
     /// Whether the command can only be used in dms or guilds; or both.
@@ -226,6 +228,7 @@ pub struct GroupOptions {
     pub help_available: bool,
     pub allowed_roles: &'static [&'static str],
     pub required_permissions: Permissions,
+    pub bot_required_permissions: Permissions,
     pub checks: &'static [&'static Check],
     pub default_command: Option<&'static Command>,
     pub description: Option<&'static str>,
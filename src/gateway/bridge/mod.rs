@@ -58,7 +58,22 @@ use tokio::sync::Mutex;
 pub use self::event::ShardStageUpdateEvent;
 pub use self::shard_manager::{ShardManager, ShardManagerOptions};
 pub use self::shard_messenger::ShardMessenger;
-pub use self::shard_queuer::ShardQueuer;
+pub use self::shard_queuer::{
+    BootErrors,
+    FifoBootStrategy,
+    IntentsFn,
+    PendingBoots,
+    PreIdentifyHook,
+    QueueLimit,
+    QueueOverflow,
+    RatelimitWait,
+    ShardBootStrategy,
+    ShardBootTiming,
+    ShardQueuer,
+    ShardSession,
+    WsUrlFn,
+    DEFAULT_BOOT_RETRIES,
+};
 pub use self::shard_runner::{ShardRunner, ShardRunnerOptions};
 pub use self::shard_runner_message::ShardRunnerMessage;
 #[cfg(feature = "voice")]
@@ -74,8 +89,42 @@ pub enum ShardQueuerMessage {
     /// Message to start a shard, where the 0-index element is the ID of the Shard to start and the
     /// 1-index element is the total shards in use.
     Start(ShardId, ShardId),
+    /// Message to start a contiguous range of shards, booting them one after another in order.
+    ///
+    /// This is useful when a process only runs some of the shards in use, as each boot still waits
+    /// for the IDENTIFY ratelimit.
+    StartRange {
+        /// The ID of the first shard to start.
+        start: ShardId,
+        /// The ID of the last shard to start, inclusive.
+        end: ShardId,
+        /// The total number of shards in use.
+        total: u32,
+    },
+    /// Message to start a shard again after it has been shut down, resuming its previous session
+    /// if there is one.
+    Restart {
+        /// The ID of the shard to restart.
+        id: ShardId,
+        /// The total number of shards in use.
+        total: u32,
+        /// The session to resume, in which case the shard falls back to identifying only if
+        /// sending the RESUME fails or Discord invalidates the session.
+        session: Option<ShardSession>,
+    },
     /// Message to shutdown the shard queuer.
     Shutdown,
+    /// Message to shutdown the shard queuer after draining its queue and closing the connections
+    /// of all running shards.
+    ///
+    /// The queuer waits for the shards to disconnect, up to a timeout, before returning.
+    ShutdownGraceful,
+    /// Message to raise the total number of shards in use, queueing the additional shards for
+    /// booting and restarting the existing ones so that they identify with the new total.
+    AddShards {
+        /// The new total number of shards in use.
+        new_total: u32,
+    },
 }
 
 /// Information about a [`ShardRunner`].
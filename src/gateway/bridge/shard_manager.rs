@@ -6,13 +6,30 @@ use std::time::Duration;
 
 use futures::channel::mpsc::{self, UnboundedReceiver as Receiver, UnboundedSender as Sender};
 use futures::SinkExt;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{broadcast, Mutex, RwLock};
 use tracing::{info, instrument, warn};
 use typemap_rev::TypeMap;
 
 #[cfg(feature = "voice")]
 use super::VoiceGatewayManager;
-use super::{ShardId, ShardQueuer, ShardQueuerMessage, ShardRunnerInfo};
+use super::{
+    BootErrors,
+    FifoBootStrategy,
+    IntentsFn,
+    PendingBoots,
+    PreIdentifyHook,
+    QueueLimit,
+    RatelimitWait,
+    ShardBootStrategy,
+    ShardBootTiming,
+    ShardId,
+    ShardQueuer,
+    ShardQueuerMessage,
+    ShardRunnerInfo,
+    ShardSession,
+    WsUrlFn,
+    DEFAULT_BOOT_RETRIES,
+};
 #[cfg(feature = "cache")]
 use crate::cache::Cache;
 use crate::client::{EventHandler, RawEventHandler};
@@ -24,6 +41,9 @@ use crate::internal::prelude::*;
 use crate::internal::tokio::spawn_named;
 use crate::model::gateway::GatewayIntents;
 
+/// The number of latency updates buffered for each subscriber before the oldest are dropped.
+const LATENCY_UPDATES_CAPACITY: usize = 64;
+
 /// A manager for handling the status of shards by starting them, restarting them, and stopping
 /// them when required.
 ///
@@ -82,10 +102,19 @@ use crate::model::gateway::GatewayIntents;
 ///     # #[cfg(feature = "voice")]
 ///     # voice_manager: None,
 ///     ws_url,
+///     ws_url_fn: None,
 ///     # #[cfg(feature = "cache")]
 ///     # cache: unimplemented!(),
 ///     # http,
 ///     intents: GatewayIntents::non_privileged(),
+///     intents_fn: None,
+///     boot_strategy: None,
+///     pre_identify: None,
+///     boot_timings: None,
+///     ratelimit_waits: None,
+///     boot_retries: None,
+///     queue_limit: None,
+///     boot_jitter: None,
 ///     presence: None,
 /// });
 /// # Ok(())
@@ -108,7 +137,10 @@ pub struct ShardManager {
     /// The total shards in use, 1-indexed.
     shard_total: u32,
     shard_queuer: Sender<ShardQueuerMessage>,
+    pending: PendingBoots,
+    boot_errors: BootErrors,
     gateway_intents: GatewayIntents,
+    latency_tx: broadcast::Sender<(ShardId, Duration)>,
 }
 
 impl ShardManager {
@@ -120,6 +152,9 @@ impl ShardManager {
         let (shard_queue_tx, shard_queue_rx) = mpsc::unbounded();
 
         let runners = Arc::new(Mutex::new(HashMap::new()));
+        let pending = PendingBoots::default();
+        let boot_errors = BootErrors::default();
+        let (latency_tx, _) = broadcast::channel(LATENCY_UPDATES_CAPACITY);
 
         let manager = Arc::new(Mutex::new(Self {
             return_value_tx,
@@ -128,7 +163,10 @@ impl ShardManager {
             shard_queuer: shard_queue_tx,
             shard_total: opt.shard_total,
             runners: Arc::clone(&runners),
+            pending: pending.clone(),
+            boot_errors: boot_errors.clone(),
             gateway_intents: opt.intents,
+            latency_tx,
         }));
 
         let mut shard_queuer = ShardQueuer {
@@ -140,15 +178,28 @@ impl ShardManager {
             last_start: None,
             manager: Arc::clone(&manager),
             queue: VecDeque::new(),
+            total: None,
+            sessions: HashMap::new(),
+            pending,
+            boot_errors,
+            boot_strategy: opt.boot_strategy.unwrap_or_else(|| Box::new(FifoBootStrategy)),
             runners,
             rx: shard_queue_rx,
             #[cfg(feature = "voice")]
             voice_manager: opt.voice_manager,
             ws_url: opt.ws_url,
+            ws_url_fn: opt.ws_url_fn,
             #[cfg(feature = "cache")]
             cache: opt.cache,
             http: opt.http,
             intents: opt.intents,
+            intents_fn: opt.intents_fn,
+            pre_identify: opt.pre_identify,
+            boot_timings: opt.boot_timings,
+            ratelimit_waits: opt.ratelimit_waits,
+            boot_retries: opt.boot_retries.unwrap_or(DEFAULT_BOOT_RETRIES),
+            queue_limit: opt.queue_limit,
+            boot_jitter: opt.boot_jitter,
             presence: opt.presence,
         };
 
@@ -207,6 +258,9 @@ impl ShardManager {
     /// This sends a shutdown signal to a shard's associated [`ShardRunner`], and then queues a
     /// initialization of a shard runner for the same shard via the [`ShardQueuer`].
     ///
+    /// If the shard had a session, the new connection attempts to resume it, falling back to
+    /// identifying anew if that fails.
+    ///
     /// # Examples
     ///
     /// Creating a client and then restarting a shard by ID:
@@ -239,11 +293,29 @@ impl ShardManager {
     #[instrument(skip(self))]
     pub async fn restart(&mut self, shard_id: ShardId) {
         info!("Restarting shard {}", shard_id);
-        self.shutdown(shard_id, 4000).await;
 
-        let shard_total = self.shard_total;
+        let runner = self.runners.lock().await.get(&shard_id).map(|r| Arc::clone(&r.shard));
+        let session = match runner {
+            Some(shard) => {
+                let shard = shard.lock().await;
+                shard.session_id().map(|session_id| ShardSession {
+                    session_id: session_id.clone(),
+                    seq: shard.seq(),
+                })
+            },
+            None => None,
+        };
+
+        // Closing with a code other than 1000 keeps the session resumable.
+        self.shutdown(shard_id, 4000).await;
 
-        self.boot([shard_id, ShardId(shard_total)]);
+        info!("Telling shard queuer to restart shard {}", shard_id);
+        self.pending.push(shard_id);
+        drop(self.shard_queuer.unbounded_send(ShardQueuerMessage::Restart {
+            id: shard_id,
+            total: self.shard_total,
+            session,
+        }));
     }
 
     /// Returns the [`ShardId`]s of the shards that have been instantiated and currently have a
@@ -255,6 +327,68 @@ impl ShardManager {
         self.runners.lock().await.keys().copied().collect()
     }
 
+    /// Raises the total number of shards in use to `new_total`.
+    ///
+    /// The [`ShardQueuer`] boots the additional shards and restarts the existing ones, so that
+    /// they identify with the new total. This is typically done when the gateway's recommended
+    /// shard count has grown.
+    #[instrument(skip(self))]
+    pub fn add_shards(&mut self, new_total: u32) {
+        if new_total <= self.shard_total {
+            warn!("New shard total {} is not above the current one", new_total);
+            return;
+        }
+
+        info!("Adding shards up to a total of {}", new_total);
+
+        self.shard_init += new_total - self.shard_total;
+        self.shard_total = new_total;
+
+        let msg = ShardQueuerMessage::AddShards {
+            new_total,
+        };
+        drop(self.shard_queuer.unbounded_send(msg));
+    }
+
+    /// Returns the number of shards that have been queued for booting but have not yet been
+    /// started.
+    ///
+    /// Shards whose boot failed and was re-queued are still counted.
+    #[must_use]
+    pub fn pending_boots(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns the [`ShardId`]s of the shards that have been queued for booting but have not yet
+    /// been started, in the order they were queued.
+    #[must_use]
+    pub fn pending_shards(&self) -> Vec<ShardId> {
+        self.pending.ids()
+    }
+
+    /// Returns the reason the last boot of the given shard failed, if it has been re-queued and
+    /// not yet started since.
+    #[must_use]
+    pub fn last_boot_error(&self, shard_id: ShardId) -> Option<String> {
+        self.boot_errors.get(shard_id)
+    }
+
+    /// Returns the reason the last boot of each shard that is failing to start failed.
+    #[must_use]
+    pub fn boot_errors(&self) -> HashMap<ShardId, String> {
+        self.boot_errors.all()
+    }
+
+    /// Subscribes to the latency measured by each shard, which is broadcast as `(ShardId,
+    /// Duration)` every time a shard receives a heartbeat acknowledgement.
+    ///
+    /// The latest latency of each shard can still be polled through [`ShardRunnerInfo::latency`].
+    /// Receivers which fall behind will skip the oldest updates.
+    #[must_use]
+    pub fn subscribe_latency(&self) -> broadcast::Receiver<(ShardId, Duration)> {
+        self.latency_tx.subscribe()
+    }
+
     /// Attempts to shut down the shard runner by Id.
     ///
     /// Returns a boolean indicating whether a shard runner was present. This is _not_ necessary an
@@ -296,6 +430,7 @@ impl ShardManager {
     fn boot(&mut self, shard_info: [ShardId; 2]) {
         info!("Telling shard queuer to start shard {}", shard_info[0]);
 
+        self.pending.push(shard_info[0]);
         let msg = ShardQueuerMessage::Start(shard_info[0], shard_info[1]);
 
         drop(self.shard_queuer.unbounded_send(msg));
@@ -328,6 +463,39 @@ impl ShardManager {
             runner.stage = stage;
         }
     }
+
+    pub(crate) fn broadcast_latency(&self, id: ShardId, latency: Duration) {
+        // Sending only fails when nobody is subscribed, in which case the update is not needed.
+        drop(self.latency_tx.send((id, latency)));
+    }
+}
+
+#[cfg(test)]
+impl ShardManager {
+    /// Creates a manager without running a [`ShardQueuer`] for it, for
+    /// [`ShardQueuer::offline`].
+    pub(crate) fn offline(
+        runners: Arc<Mutex<HashMap<ShardId, ShardRunnerInfo>>>,
+        pending: PendingBoots,
+        boot_errors: BootErrors,
+    ) -> Arc<Mutex<Self>> {
+        let (return_value_tx, _) = mpsc::unbounded();
+        let (shard_queuer, _) = mpsc::unbounded();
+        let (latency_tx, _) = broadcast::channel(LATENCY_UPDATES_CAPACITY);
+
+        Arc::new(Mutex::new(Self {
+            return_value_tx,
+            runners,
+            shard_index: 0,
+            shard_init: 0,
+            shard_total: 0,
+            shard_queuer,
+            pending,
+            boot_errors,
+            gateway_intents: GatewayIntents::empty(),
+            latency_tx,
+        }))
+    }
 }
 
 impl Drop for ShardManager {
@@ -360,9 +528,30 @@ pub struct ShardManagerOptions {
     #[cfg(feature = "voice")]
     pub voice_manager: Option<Arc<dyn VoiceGatewayManager>>,
     pub ws_url: Arc<Mutex<String>>,
+    /// Computes the gateway URL for a specific shard, overriding [`Self::ws_url`] when set.
+    pub ws_url_fn: Option<WsUrlFn>,
     #[cfg(feature = "cache")]
     pub cache: Arc<Cache>,
     pub http: Arc<Http>,
     pub intents: GatewayIntents,
+    /// Computes the intents for a specific shard, overriding [`Self::intents`] when set.
+    pub intents_fn: Option<IntentsFn>,
+    /// The order in which to boot queued shards, defaulting to [`FifoBootStrategy`].
+    pub boot_strategy: Option<Box<dyn ShardBootStrategy>>,
+    /// Awaited before each shard connects and identifies, such as to acquire a lock shared
+    /// between processes.
+    pub pre_identify: Option<PreIdentifyHook>,
+    /// A channel to send the timing of each shard boot attempt to.
+    pub boot_timings: Option<Sender<ShardBootTiming>>,
+    /// A channel to send each sleep for the ratelimit between IDENTIFYs to.
+    pub ratelimit_waits: Option<Sender<RatelimitWait>>,
+    /// How many times to retry connecting a shard after a transient error, defaulting to
+    /// [`DEFAULT_BOOT_RETRIES`].
+    pub boot_retries: Option<u8>,
+    /// The maximum number of shards queued for booting, unbounded if unset.
+    pub queue_limit: Option<QueueLimit>,
+    /// The fraction of the wait between IDENTIFYs to randomly add to it, to spread out the boots
+    /// of processes started at the same time. No jitter is added if unset.
+    pub boot_jitter: Option<f64>,
     pub presence: Option<PresenceData>,
 }
@@ -1,13 +1,19 @@
+use std::collections::hash_map::RandomState;
 use std::collections::{HashMap, VecDeque};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 #[cfg(feature = "framework")]
 use std::sync::OnceLock;
 
-use futures::channel::mpsc::UnboundedReceiver as Receiver;
+use futures::channel::mpsc::{UnboundedReceiver as Receiver, UnboundedSender as Sender};
+use futures::future::BoxFuture;
+use futures::Future;
 use futures::StreamExt;
 use tokio::sync::{Mutex, RwLock};
 use tokio::time::{sleep, timeout, Duration, Instant};
-use tracing::{debug, info, instrument, warn};
+use tokio_tungstenite::tungstenite::error::Error as TungsteniteError;
+use tracing::{debug, error, info, instrument, warn};
 use typemap_rev::TypeMap;
 
 #[cfg(feature = "voice")]
@@ -26,13 +32,180 @@ use crate::cache::Cache;
 use crate::client::{EventHandler, RawEventHandler};
 #[cfg(feature = "framework")]
 use crate::framework::Framework;
-use crate::gateway::{ConnectionStage, PresenceData, Shard};
+use crate::gateway::{ConnectionStage, GatewayError, PresenceData, ReconnectType, Shard};
 use crate::http::Http;
 use crate::internal::prelude::*;
 use crate::internal::tokio::spawn_named;
 use crate::model::gateway::{GatewayIntents, ShardInfo};
 
 const WAIT_BETWEEN_BOOTS_IN_SECONDS: u64 = 5;
+const GRACEFUL_SHUTDOWN_TIMEOUT_IN_SECONDS: u64 = 10;
+const BOOT_RETRY_BACKOFF_IN_MILLISECONDS: u64 = 500;
+
+/// The number of times a shard's connection is retried on transient errors by default.
+pub const DEFAULT_BOOT_RETRIES: u8 = 2;
+
+/// A function computing the [`GatewayIntents`] to identify a specific shard with.
+pub type IntentsFn = Arc<dyn Fn(ShardInfo) -> GatewayIntents + Send + Sync>;
+
+/// A function computing the URL of the gateway a specific shard connects to.
+pub type WsUrlFn = Arc<dyn Fn(ShardInfo) -> String + Send + Sync>;
+
+/// An async function run right before a specific shard identifies with the gateway.
+pub type PreIdentifyHook = Arc<dyn Fn(ShardInfo) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Timing of a single shard boot, emitted by the [`ShardQueuer`] after each boot attempt.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ShardBootTiming {
+    /// The ID of the shard that was booted.
+    pub id: ShardId,
+    /// How long the boot waited for the ratelimit between IDENTIFYs.
+    pub wait: Duration,
+    /// How long it took to connect and start the shard, including any [`PreIdentifyHook`].
+    pub connect: Duration,
+    /// Whether the shard attempted to resume its previous session or identified anew.
+    pub kind: ReconnectType,
+    /// The error the boot failed with, in which case it has been re-queued.
+    pub error: Option<Error>,
+}
+
+/// A sleep of the [`ShardQueuer`] to honour the ratelimit between IDENTIFYs, emitted right before
+/// the shard is started.
+///
+/// No event is emitted for boots that did not have to wait, so the events add up to the total time
+/// spent waiting on the ratelimit.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct RatelimitWait {
+    /// The ID of the shard that waited to be booted.
+    pub id: ShardId,
+    /// How long the queuer slept.
+    pub slept: Duration,
+}
+
+/// The gateway session of a shard being restarted, which the new connection attempts to resume.
+#[derive(Clone, Debug)]
+pub struct ShardSession {
+    /// The ID of the session to resume.
+    pub session_id: String,
+    /// The sequence number of the last event received in the session.
+    pub seq: u64,
+}
+
+/// Decides the order in which the [`ShardQueuer`] boots queued shards.
+pub trait ShardBootStrategy: Send {
+    /// Removes and returns the next shard to boot from the queue, if any.
+    fn next(&mut self, queue: &mut VecDeque<ShardInfo>) -> Option<ShardInfo>;
+}
+
+/// The default [`ShardBootStrategy`], booting shards in the order they were queued.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FifoBootStrategy;
+
+impl ShardBootStrategy for FifoBootStrategy {
+    fn next(&mut self, queue: &mut VecDeque<ShardInfo>) -> Option<ShardInfo> {
+        queue.pop_front()
+    }
+}
+
+/// A cap on the number of shards in the [`ShardQueuer`]'s queue, guarding against unbounded
+/// growth such as from a shard failing to boot over and over.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct QueueLimit {
+    /// The maximum number of queued shards.
+    pub max: usize,
+    /// Which shard to drop when queueing one more would exceed [`Self::max`].
+    pub overflow: QueueOverflow,
+}
+
+/// Which shard a [`QueueLimit`] drops when the queue is full.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum QueueOverflow {
+    /// Drops the shard that has been queued the longest, making room for the new one.
+    #[default]
+    DropOldest,
+    /// Drops the shard being queued, keeping the queue as it is.
+    DropNewest,
+}
+
+/// The shards that have been told to boot but have not yet been started, shared between the
+/// [`ShardManager`] and the [`ShardQueuer`].
+///
+/// A shard stays pending while its boot is re-queued after a failure.
+#[derive(Clone, Debug, Default)]
+pub struct PendingBoots {
+    count: Arc<AtomicUsize>,
+    ids: Arc<std::sync::Mutex<VecDeque<ShardId>>>,
+}
+
+impl PendingBoots {
+    /// Returns the number of shards waiting to be started.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether there are no shards waiting to be started.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a snapshot of the IDs of the shards waiting to be started, in the order they were
+    /// queued.
+    #[must_use]
+    pub fn ids(&self) -> Vec<ShardId> {
+        self.ids.lock().expect("poison").iter().copied().collect()
+    }
+
+    pub(crate) fn push(&self, id: ShardId) {
+        let mut ids = self.ids.lock().expect("poison");
+        ids.push_back(id);
+        self.count.store(ids.len(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn remove(&self, id: ShardId) {
+        let mut ids = self.ids.lock().expect("poison");
+        if let Some(index) = ids.iter().position(|queued| *queued == id) {
+            ids.remove(index);
+        }
+        self.count.store(ids.len(), Ordering::Relaxed);
+    }
+}
+
+/// The reason each shard's last boot failed, shared between the [`ShardManager`] and the
+/// [`ShardQueuer`].
+///
+/// A shard's error is kept while its boot is re-queued, and cleared once it has been started.
+#[derive(Clone, Debug, Default)]
+pub struct BootErrors {
+    errors: Arc<std::sync::Mutex<HashMap<ShardId, String>>>,
+}
+
+impl BootErrors {
+    /// Returns the reason the last boot of the given shard failed, if it has not since been
+    /// started.
+    #[must_use]
+    pub fn get(&self, id: ShardId) -> Option<String> {
+        self.errors.lock().expect("poison").get(&id).cloned()
+    }
+
+    /// Returns a snapshot of the reasons the last boot of each failing shard failed.
+    #[must_use]
+    pub fn all(&self) -> HashMap<ShardId, String> {
+        self.errors.lock().expect("poison").clone()
+    }
+
+    pub(crate) fn insert(&self, id: ShardId, why: &Error) {
+        self.errors.lock().expect("poison").insert(id, why.to_string());
+    }
+
+    pub(crate) fn remove(&self, id: ShardId) {
+        self.errors.lock().expect("poison").remove(&id);
+    }
+}
 
 /// The shard queuer is a simple loop that runs indefinitely to manage the startup of shards.
 ///
@@ -64,6 +237,24 @@ pub struct ShardQueuer {
     ///
     /// This will typically be filled with previously failed boots.
     pub queue: VecDeque<ShardInfo>,
+    /// The maximum length of [`Self::queue`], unbounded if unset.
+    pub queue_limit: Option<QueueLimit>,
+    /// The total number of shards in use, set by the first start and changed only when adding
+    /// shards.
+    ///
+    /// Starts with a different total are rejected, as they would route events inconsistently.
+    pub total: Option<u32>,
+    /// The sessions of restarting shards, to be resumed when they are next booted.
+    ///
+    /// A session is kept while its shard fails to connect, and dropped once a RESUME has been
+    /// attempted, so that further boots fall back to identifying.
+    pub sessions: HashMap<ShardId, ShardSession>,
+    /// The shards that have been told to boot but have not yet been started.
+    pub pending: PendingBoots,
+    /// The reason each shard's last boot failed, cleared once the shard has been started.
+    pub boot_errors: BootErrors,
+    /// The strategy picking which queued shard to boot next.
+    pub boot_strategy: Box<dyn ShardBootStrategy>,
     /// A copy of the map of shard runners.
     pub runners: Arc<Mutex<HashMap<ShardId, ShardRunnerInfo>>>,
     /// A receiver channel for the shard queuer to be told to start shards.
@@ -73,10 +264,29 @@ pub struct ShardQueuer {
     pub voice_manager: Option<Arc<dyn VoiceGatewayManager + 'static>>,
     /// A copy of the URL to use to connect to the gateway.
     pub ws_url: Arc<Mutex<String>>,
+    /// Computes the gateway URL for a specific shard, overriding [`Self::ws_url`] when set.
+    pub ws_url_fn: Option<WsUrlFn>,
     #[cfg(feature = "cache")]
     pub cache: Arc<Cache>,
     pub http: Arc<Http>,
     pub intents: GatewayIntents,
+    /// Computes the intents for a specific shard, overriding [`Self::intents`] when set.
+    pub intents_fn: Option<IntentsFn>,
+    /// Awaited before each shard connects and identifies, counting towards its boot time.
+    pub pre_identify: Option<PreIdentifyHook>,
+    /// A channel to send the timing of each boot attempt to.
+    pub boot_timings: Option<Sender<ShardBootTiming>>,
+    /// A channel to send each sleep for the ratelimit between IDENTIFYs to.
+    pub ratelimit_waits: Option<Sender<RatelimitWait>>,
+    /// How many times to retry connecting a shard after a transient error, before re-queueing it.
+    pub boot_retries: u8,
+    /// The fraction of the wait between IDENTIFYs to randomly add to it, such as `0.2` to wait up
+    /// to 20% longer.
+    ///
+    /// This spreads out the boots of several processes started at the same time. The wait is only
+    /// ever lengthened, never shortened below the ratelimit. Fractions are clamped to `0.0..=1.0`,
+    /// with `NaN` treated as zero.
+    pub boot_jitter: Option<f64>,
     pub presence: Option<PresenceData>,
 }
 
@@ -93,9 +303,26 @@ impl ShardQueuer {
     ///    passed
     /// 3. Start the shard by ID
     ///
+    /// A [`ShardQueuerMessage::StartRange`] is handled as a [`ShardQueuerMessage::Start`] of each
+    /// shard in the range, in order.
+    ///
+    /// A start of a shard that is already running or already queued is dropped, so that a shard
+    /// is not booted twice.
+    ///
+    /// A [`ShardQueuerMessage::Restart`] is handled the same way, except that the shard resumes
+    /// the given session if there is one, which skips the wait between IDENTIFYs, and it is
+    /// started even if it is running or queued. A queued boot of the shard is then discarded.
+    ///
     /// If a [`ShardQueuerMessage::Shutdown`] is received, this will return and the loop will be
     /// over.
     ///
+    /// If a [`ShardQueuerMessage::AddShards`] is received, the additional shards as well as
+    /// restarts of the existing ones are queued, to be booted one at a time.
+    ///
+    /// If a [`ShardQueuerMessage::ShutdownGraceful`] is received, this will discard all queued
+    /// starts, tell every running shard to close its connection, and wait for them to disconnect
+    /// before returning.
+    ///
     /// **Note**: This should be run in its own thread due to the blocking nature of the loop.
     #[instrument(skip(self))]
     pub async fn run(&mut self) {
@@ -110,13 +337,55 @@ impl ShardQueuer {
                     debug!("[Shard Queuer] Received to shutdown.");
                     break;
                 },
+                Ok(Some(ShardQueuerMessage::ShutdownGraceful)) => {
+                    debug!("[Shard Queuer] Received to shutdown gracefully.");
+                    self.drain().await;
+                    break;
+                },
+                Ok(Some(ShardQueuerMessage::AddShards {
+                    new_total,
+                })) => {
+                    debug!("[Shard Queuer] Received to add shards up to {}.", new_total);
+                    self.add_shards(new_total).await;
+                },
                 Ok(Some(ShardQueuerMessage::Start(id, total))) => {
                     debug!("[Shard Queuer] Received to start shard {} of {}.", id.0, total.0);
-                    self.checked_start(id, total.0).await;
+                    if self.check_total(id, total.0) && !self.coalesce_start(id).await {
+                        self.checked_start(id, total.0).await;
+                    }
+                },
+                Ok(Some(ShardQueuerMessage::StartRange {
+                    start,
+                    end,
+                    total,
+                })) => {
+                    debug!(
+                        "[Shard Queuer] Received to start shards {} to {} of {}.",
+                        start.0, end.0, total
+                    );
+                    for id in (start.0..=end.0).map(ShardId) {
+                        if self.check_total(id, total) && !self.coalesce_start(id).await {
+                            self.checked_start(id, total).await;
+                        }
+                    }
+                },
+                Ok(Some(ShardQueuerMessage::Restart {
+                    id,
+                    total,
+                    session,
+                })) => {
+                    debug!("[Shard Queuer] Received to restart shard {} of {}.", id.0, total);
+                    if self.check_total(id, total) {
+                        if let Some(session) = session {
+                            self.sessions.insert(id, session);
+                        }
+                        self.dequeue(id);
+                        self.checked_start(id, total).await;
+                    }
                 },
                 Ok(None) => break,
                 Err(_) => {
-                    if let Some(shard) = self.queue.pop_front() {
+                    if let Some(shard) = self.boot_strategy.next(&mut self.queue) {
                         self.checked_start(shard.id, shard.total).await;
                     }
                 },
@@ -124,12 +393,179 @@ impl ShardQueuer {
         }
     }
 
+    /// Returns whether a shard may be started with the given total, recording the total if it is
+    /// the first one seen.
+    fn check_total(&mut self, id: ShardId, total: u32) -> bool {
+        let expected = *self.total.get_or_insert(total);
+        if expected == total {
+            return true;
+        }
+
+        warn!(
+            "[Shard Queuer] Rejecting start of shard {} with total {}, expected {}",
+            id, total, expected
+        );
+        self.pending.remove(id);
+
+        false
+    }
+
+    /// Returns whether a start of the shard should be dropped, because the shard is already
+    /// running or queued to be booted.
+    async fn coalesce_start(&mut self, id: ShardId) -> bool {
+        let queued = self.queue.iter().any(|shard| shard.id == id);
+        if !queued && !self.runners.lock().await.contains_key(&id) {
+            return false;
+        }
+
+        debug!("[Shard Queuer] Dropping start of shard {}, which is already running or queued", id);
+        self.pending.remove(id);
+
+        true
+    }
+
+    /// Removes the queued boot of the shard, if any, as the shard is about to be started right
+    /// away.
+    fn dequeue(&mut self, id: ShardId) {
+        let queued = self.queue.len();
+        self.queue.retain(|shard| shard.id != id);
+        if self.queue.len() < queued {
+            self.pending.remove(id);
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn add_shards(&mut self, new_total: u32) {
+        let mut old_total = self.total.replace(new_total).unwrap_or(0);
+        // Sessions are bound to the total they were identified with, so none can be resumed.
+        self.sessions.clear();
+
+        for shard in &mut self.queue {
+            old_total = old_total.max(shard.total);
+            shard.total = new_total;
+        }
+
+        // Running shards are restarted one by one as their turn in the queue comes up, so that
+        // they identify with the new total.
+        let runners = self
+            .runners
+            .lock()
+            .await
+            .iter()
+            .map(|(id, runner)| (*id, Arc::clone(&runner.shard)))
+            .collect::<Vec<_>>();
+        for (id, shard) in runners {
+            old_total = old_total.max(shard.lock().await.shard_info().total);
+
+            self.pending.push(id);
+            self.enqueue(ShardInfo::new(id, new_total));
+        }
+
+        for id in old_total..new_total {
+            self.pending.push(ShardId(id));
+            self.enqueue(ShardInfo::new(ShardId(id), new_total));
+        }
+    }
+
+    /// Queues a shard to be booted, dropping one per the [`Self::queue_limit`] if the queue is
+    /// full.
+    ///
+    /// A shard which is already queued is not queued again.
+    fn enqueue(&mut self, shard: ShardInfo) {
+        if self.queue.iter().any(|queued| queued.id == shard.id) {
+            debug!("[Shard Queuer] Shard {} is already queued", shard.id);
+            self.pending.remove(shard.id);
+            return;
+        }
+
+        let Some(limit) = self.queue_limit else {
+            self.queue.push_back(shard);
+            return;
+        };
+
+        if self.queue.len() < limit.max {
+            self.queue.push_back(shard);
+            return;
+        }
+
+        let dropped = match limit.overflow {
+            QueueOverflow::DropOldest => {
+                self.queue.push_back(shard);
+                self.queue.pop_front()
+            },
+            QueueOverflow::DropNewest => Some(shard),
+        };
+
+        if let Some(dropped) = dropped {
+            warn!(
+                "[Shard Queuer] Queue is full at {} shards, dropping start of shard {}",
+                limit.max, dropped.id
+            );
+            self.pending.remove(dropped.id);
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn drain(&mut self) {
+        // Stop accepting new starts, both those already queued and those still in the channel.
+        for shard in self.queue.drain(..) {
+            self.pending.remove(shard.id);
+        }
+
+        while let Ok(Some(msg)) = self.rx.try_next() {
+            match msg {
+                ShardQueuerMessage::Start(id, _)
+                | ShardQueuerMessage::Restart {
+                    id, ..
+                } => self.pending.remove(id),
+                ShardQueuerMessage::StartRange {
+                    start,
+                    end,
+                    ..
+                } => {
+                    for id in start.0..=end.0 {
+                        self.pending.remove(ShardId(id));
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        shutdown_runners(self.runners.lock().await.values().map(|runner| &runner.runner_tx));
+
+        let disconnected = async {
+            loop {
+                let runners = self.runners.lock().await;
+                if runners.values().all(|runner| {
+                    runner.stage == ConnectionStage::Disconnected
+                        || runner
+                            .shard
+                            .try_lock()
+                            .is_ok_and(|shard| shard.stage() == ConnectionStage::Disconnected)
+                }) {
+                    return;
+                }
+                drop(runners);
+
+                sleep(Duration::from_millis(100)).await;
+            }
+        };
+
+        let duration = Duration::from_secs(GRACEFUL_SHUTDOWN_TIMEOUT_IN_SECONDS);
+        if timeout(duration, disconnected).await.is_err() {
+            warn!("[Shard Queuer] Timed out waiting for shards to disconnect");
+        }
+    }
+
     #[instrument(skip(self))]
-    async fn check_last_start(&mut self) {
+    async fn check_last_start(&mut self, id: ShardId) {
         let Some(instant) = self.last_start else {return};
 
         // We must wait 5 seconds between IDENTIFYs to avoid session invalidations.
-        let duration = Duration::from_secs(WAIT_BETWEEN_BOOTS_IN_SECONDS);
+        let mut duration = Duration::from_secs(WAIT_BETWEEN_BOOTS_IN_SECONDS);
+        if let Some(jitter) = self.boot_jitter {
+            duration = jittered(duration, jitter);
+        }
         let elapsed = instant.elapsed();
 
         if elapsed >= duration {
@@ -139,36 +575,115 @@ impl ShardQueuer {
         let to_sleep = duration - elapsed;
 
         sleep(to_sleep).await;
+
+        if let Some(ratelimit_waits) = &self.ratelimit_waits {
+            drop(ratelimit_waits.unbounded_send(RatelimitWait {
+                id,
+                slept: to_sleep,
+            }));
+        }
     }
 
     #[instrument(skip(self))]
     async fn checked_start(&mut self, id: ShardId, total: u32) {
         debug!("[Shard Queuer] Checked start for shard {} out of {}", id, total);
-        self.check_last_start().await;
+        let kind = boot_kind(self.sessions.get(&id));
+
+        // Only IDENTIFYs are ratelimited, so a resuming shard can start right away.
+        let waiting = Instant::now();
+        if matches!(kind, ReconnectType::Reidentify) {
+            self.check_last_start(id).await;
+        }
+        let wait = waiting.elapsed();
+
+        let connecting = Instant::now();
+        let result = self.start(id, total).await;
+        let connect = connecting.elapsed();
+
+        let error = match result {
+            Ok(()) => {
+                self.pending.remove(id);
+                self.boot_errors.remove(id);
+                None
+            },
+            Err(why) => {
+                self.boot_errors.insert(id, &why);
 
-        if let Err(why) = self.start(id, total).await {
-            warn!("[Shard Queuer] Err starting shard {}: {:?}", id, why);
-            info!("[Shard Queuer] Re-queueing start of shard {}", id);
+                // Retrying can't fix an error such as invalid authentication or disallowed
+                // intents, so only transient errors are re-queued.
+                if is_transient(&why) {
+                    warn!("[Shard Queuer] Err starting shard {}: {:?}", id, why);
+                    info!("[Shard Queuer] Re-queueing start of shard {}", id);
+
+                    self.enqueue(ShardInfo::new(id, total));
+                } else {
+                    error!("[Shard Queuer] Fatal err starting shard {}: {:?}", id, why);
+
+                    self.pending.remove(id);
+                }
+                Some(why)
+            },
+        };
 
-            self.queue.push_back(ShardInfo::new(id, total));
+        if matches!(kind, ReconnectType::Reidentify) {
+            self.last_start = Some(Instant::now());
         }
 
-        self.last_start = Some(Instant::now());
+        if let Some(boot_timings) = &self.boot_timings {
+            drop(boot_timings.unbounded_send(ShardBootTiming {
+                id,
+                wait,
+                connect,
+                kind,
+                error,
+            }));
+        }
+    }
+
+    /// Returns the intents to identify the given shard with.
+    fn intents_for(&self, shard_info: ShardInfo) -> GatewayIntents {
+        match &self.intents_fn {
+            Some(intents_fn) => intents_fn(shard_info),
+            None => self.intents,
+        }
+    }
+
+    /// Returns the URL of the gateway the given shard connects to.
+    ///
+    /// A URL computed for the shard is not shared with the other shards.
+    fn ws_url_for(&self, shard_info: ShardInfo) -> Arc<Mutex<String>> {
+        match &self.ws_url_fn {
+            Some(ws_url_fn) => Arc::new(Mutex::new(ws_url_fn(shard_info))),
+            None => Arc::clone(&self.ws_url),
+        }
     }
 
     #[instrument(skip(self))]
     async fn start(&mut self, id: ShardId, total: u32) -> Result<()> {
         let shard_info = ShardInfo::new(id, total);
 
-        let mut shard = Shard::new(
-            Arc::clone(&self.ws_url),
-            self.http.token(),
-            shard_info,
-            self.intents,
-            self.presence.clone(),
-        )
+        if let Some(pre_identify) = &self.pre_identify {
+            pre_identify(shard_info).await;
+        }
+
+        let intents = self.intents_for(shard_info);
+        let ws_url = self.ws_url_for(shard_info);
+        let mut shard = retry_transient(self.boot_retries, || {
+            Shard::new(
+                Arc::clone(&ws_url),
+                self.http.token(),
+                shard_info,
+                intents,
+                self.presence.clone(),
+            )
+        })
         .await?;
 
+        if let Some(session) = self.sessions.remove(&id) {
+            info!("[Shard Queuer] Resuming session of shard {}", id);
+            shard.resume_session(session.session_id, session.seq).await?;
+        }
+
         let cloned_http = Arc::clone(&self.http);
         shard.set_application_id_callback(move |id| cloned_http.set_application_id(id));
 
@@ -189,10 +704,17 @@ impl ShardQueuer {
         let runner_info = ShardRunnerInfo {
             latency: None,
             runner_tx: ShardMessenger::new(&runner),
-            stage: ConnectionStage::Disconnected,
+            stage: shard.stage(),
             shard: Arc::new(Mutex::new(shard)),
         };
 
+        // Replace a runner that is still running, such as one being restarted by
+        // `ShardQueuerMessage::AddShards`.
+        let previous = self.runners.lock().await.remove(&id);
+        if let Some(previous) = previous {
+            previous.shard.lock().await.shutdown(4000).await;
+        }
+
         let shard2 = Arc::clone(&runner_info.shard);
         spawn_named("shard_queuer::stop", async move {
             drop(runner.run(&shard2).await);
@@ -204,3 +726,726 @@ impl ShardQueuer {
         Ok(())
     }
 }
+
+#[cfg(test)]
+impl ShardQueuer {
+    /// Creates a queuer that never reaches Discord, to test the boot logic in isolation.
+    ///
+    /// The [`Http`] client is created with a dummy token and is never used to make requests, and
+    /// the [`Cache`] starts out empty. The gateway URL points at a closed local port, so
+    /// [`Self::start`] fails with a transient error right after the [`Self::pre_identify`] hook
+    /// has run, exercising the failure and re-queue paths.
+    ///
+    /// The [`Self::manager`] shares the runners and pending boots of this queuer, but has no
+    /// queuer of its own running, so the messages it sends are dropped. Every other field can be
+    /// replaced after construction, such as [`Self::intents_fn`] or [`Self::boot_timings`].
+    pub(crate) fn offline() -> Self {
+        let data = Arc::new(RwLock::new(TypeMap::new()));
+        #[cfg(feature = "framework")]
+        let framework = Arc::new(OnceLock::new());
+        let ws_url = Arc::new(Mutex::new(String::from("ws://127.0.0.1:1")));
+        #[cfg(feature = "cache")]
+        let cache = Arc::new(Cache::default());
+        let http = Arc::new(Http::new("token"));
+
+        let runners = Arc::default();
+        let pending = PendingBoots::default();
+        let boot_errors = BootErrors::default();
+        let manager =
+            ShardManager::offline(Arc::clone(&runners), pending.clone(), boot_errors.clone());
+
+        Self {
+            data,
+            event_handlers: vec![],
+            raw_event_handlers: vec![],
+            #[cfg(feature = "framework")]
+            framework,
+            last_start: None,
+            manager,
+            queue: VecDeque::new(),
+            queue_limit: None,
+            total: None,
+            sessions: HashMap::new(),
+            pending,
+            boot_errors,
+            boot_strategy: Box::new(FifoBootStrategy),
+            runners,
+            rx: futures::channel::mpsc::unbounded().1,
+            #[cfg(feature = "voice")]
+            voice_manager: None,
+            ws_url,
+            ws_url_fn: None,
+            #[cfg(feature = "cache")]
+            cache,
+            http,
+            intents: GatewayIntents::non_privileged(),
+            intents_fn: None,
+            pre_identify: None,
+            boot_timings: None,
+            ratelimit_waits: None,
+            boot_retries: 0,
+            boot_jitter: None,
+            presence: None,
+        }
+    }
+
+    /// Registers a runner at the given stage, as [`Self::start`] would, for a shard connected to
+    /// a [local gateway].
+    ///
+    /// Returns the receiving end of the runner's messenger.
+    ///
+    /// [local gateway]: serve_gateway
+    pub(crate) async fn insert_runner(
+        &self,
+        id: ShardId,
+        total: u32,
+        stage: ConnectionStage,
+    ) -> Receiver<crate::gateway::ShardRunnerMessage> {
+        let ws_url = Arc::new(Mutex::new(serve_gateway().await));
+        let info = ShardInfo::new(id, total);
+        let shard = Shard::new(ws_url, "token", info, self.intents, None).await.unwrap();
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        self.runners.lock().await.insert(id, ShardRunnerInfo {
+            latency: None,
+            runner_tx: ShardMessenger {
+                tx,
+                #[cfg(feature = "collector")]
+                collectors: Arc::default(),
+            },
+            stage,
+            shard: Arc::new(Mutex::new(shard)),
+        });
+
+        rx
+    }
+}
+
+/// Accepts websocket connections on a local port, standing in for the gateway, and returns its
+/// URL.
+///
+/// Connections are held open but never sent anything, so shards connected to it stay at the
+/// handshake stage.
+#[cfg(test)]
+async fn serve_gateway() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let _socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                futures::future::pending::<()>().await;
+            });
+        }
+    });
+
+    format!("ws://{addr}")
+}
+
+/// Whether a shard with the given session, if any, resumes it or identifies anew.
+fn boot_kind(session: Option<&ShardSession>) -> ReconnectType {
+    match session {
+        Some(_) => ReconnectType::Resume,
+        None => ReconnectType::Reidentify,
+    }
+}
+
+/// Whether the error is likely to go away when retrying, such as a timeout or a dropped
+/// connection, as opposed to an error like invalid authentication.
+fn is_transient(why: &Error) -> bool {
+    match why {
+        Error::Io(_) => true,
+        Error::Tungstenite(why) => {
+            matches!(why, TungsteniteError::Io(_) | TungsteniteError::ConnectionClosed)
+        },
+        Error::Gateway(why) => matches!(why, GatewayError::Closed(_) | GatewayError::ExpectedHello),
+        _ => false,
+    }
+}
+
+/// Runs `f` until it succeeds, fails with an error that is not [transient], or has been retried
+/// `retries` times, backing off a little longer between each attempt.
+///
+/// [transient]: is_transient
+async fn retry_transient<T, Fut>(retries: u8, mut f: impl FnMut() -> Fut) -> Result<T>
+where
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        match f().await {
+            Err(why) if attempt < retries && is_transient(&why) => {
+                attempt += 1;
+                debug!("[Shard Queuer] Retrying transient err, attempt {}: {:?}", attempt, why);
+
+                let backoff = BOOT_RETRY_BACKOFF_IN_MILLISECONDS * u64::from(attempt);
+                sleep(Duration::from_millis(backoff)).await;
+            },
+            result => return result,
+        }
+    }
+}
+
+/// Lengthens `duration` by a random amount of up to the fraction `jitter` of it, clamped to
+/// `0.0..=1.0`.
+fn jittered(duration: Duration, jitter: f64) -> Duration {
+    // `Duration::mul_f64` panics on infinite or overflowing results, and `NaN > 0.0` is false.
+    let jitter = if jitter > 0.0 { jitter.min(1.0) } else { 0.0 };
+    // Each `RandomState` is seeded differently, which is random enough to spread out boots.
+    let random = RandomState::new().build_hasher().finish();
+    let fraction = f64::from((random >> 32) as u32) / f64::from(u32::MAX);
+
+    duration.saturating_add(duration.mul_f64(jitter * fraction))
+}
+
+/// Tells each of the given shard runners to cleanly close its connection.
+fn shutdown_runners<'a>(runners: impl Iterator<Item = &'a ShardMessenger>) {
+    for runner in runners {
+        runner.shutdown_clean();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::mpsc;
+    use futures::FutureExt;
+
+    use super::*;
+    use crate::gateway::ShardRunnerMessage;
+
+    #[tokio::test]
+    async fn graceful_shutdown_waits_for_runners() {
+        let mut queuer = ShardQueuer::offline();
+        let mut receivers = Vec::new();
+        for id in 0..2 {
+            let receiver = queuer.insert_runner(ShardId(id), 2, ConnectionStage::Connected).await;
+            receivers.push((ShardId(id), receiver));
+        }
+
+        // Stand in for the runners, which report their shard as disconnected once told to close.
+        let runners = Arc::clone(&queuer.runners);
+        let closed = tokio::spawn(async move {
+            for (id, mut receiver) in receivers {
+                let msg = receiver.next().await;
+                assert!(matches!(msg, Some(ShardRunnerMessage::Close(1000, None))));
+                runners.lock().await.get_mut(&id).unwrap().stage = ConnectionStage::Disconnected;
+            }
+        });
+
+        let (sender, receiver) = mpsc::unbounded();
+        queuer.rx = receiver;
+        sender.unbounded_send(ShardQueuerMessage::ShutdownGraceful).unwrap();
+
+        // Returning well before the timeout means it was the disconnects that ended the wait.
+        let limit = Duration::from_secs(GRACEFUL_SHUTDOWN_TIMEOUT_IN_SECONDS / 2);
+        timeout(limit, queuer.run()).await.unwrap();
+        closed.await.unwrap();
+    }
+
+    struct HighestFirst;
+
+    impl ShardBootStrategy for HighestFirst {
+        fn next(&mut self, queue: &mut VecDeque<ShardInfo>) -> Option<ShardInfo> {
+            let (index, _) = queue.iter().enumerate().max_by_key(|(_, shard)| shard.id.0)?;
+            queue.remove(index)
+        }
+    }
+
+    #[test]
+    fn boot_strategy() {
+        let queue = (0..3).map(|id| ShardInfo::new(ShardId(id), 3)).collect::<VecDeque<_>>();
+
+        let mut fifo = queue.clone();
+        let order = std::iter::from_fn(|| FifoBootStrategy.next(&mut fifo)).map(|shard| shard.id);
+        assert_eq!(order.collect::<Vec<_>>(), vec![ShardId(0), ShardId(1), ShardId(2)]);
+
+        let mut highest = queue;
+        let order = std::iter::from_fn(|| HighestFirst.next(&mut highest)).map(|shard| shard.id);
+        assert_eq!(order.collect::<Vec<_>>(), vec![ShardId(2), ShardId(1), ShardId(0)]);
+    }
+
+    #[tokio::test]
+    async fn add_shards() {
+        let mut queuer = ShardQueuer::offline();
+        for id in 0..2 {
+            queuer.pending.push(ShardId(id));
+            queuer.queue.push_back(ShardInfo::new(ShardId(id), 2));
+        }
+
+        queuer.add_shards(4).await;
+
+        let in_queue = queuer.queue.iter().map(|shard| (shard.id, shard.total)).collect::<Vec<_>>();
+        assert_eq!(in_queue, vec![
+            (ShardId(0), 4),
+            (ShardId(1), 4),
+            (ShardId(2), 4),
+            (ShardId(3), 4)
+        ]);
+        assert_eq!(queuer.pending.ids(), vec![ShardId(0), ShardId(1), ShardId(2), ShardId(3)]);
+    }
+
+    #[tokio::test]
+    async fn add_shards_only_new() {
+        let mut queuer = ShardQueuer::offline();
+        queuer.total = Some(2);
+
+        queuer.add_shards(3).await;
+
+        let in_queue = queuer.queue.iter().map(|shard| (shard.id, shard.total)).collect::<Vec<_>>();
+        assert_eq!(in_queue, vec![(ShardId(2), 3)]);
+        assert_eq!(queuer.total, Some(3));
+    }
+
+    #[tokio::test]
+    async fn pre_identify_hook() {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let mut queuer = ShardQueuer::offline();
+        let runners = Arc::clone(&queuer.runners);
+        let hook_calls = Arc::clone(&calls);
+        queuer.pre_identify = Some(Arc::new(move |shard_info: ShardInfo| {
+            let runners = Arc::clone(&runners);
+            let hook_calls = Arc::clone(&hook_calls);
+            async move {
+                let started = runners.lock().await.contains_key(&shard_info.id);
+                hook_calls.lock().unwrap().push((shard_info.id, started));
+            }
+            .boxed()
+        }));
+
+        // There is no gateway to connect to, so the boot fails right after the hook has run.
+        assert!(queuer.start(ShardId(0), 2).await.is_err());
+        assert!(queuer.start(ShardId(1), 2).await.is_err());
+
+        assert_eq!(*calls.lock().unwrap(), vec![(ShardId(0), false), (ShardId(1), false)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn boot_timings() {
+        let (tx, mut rx) = mpsc::unbounded();
+        let mut queuer = ShardQueuer::offline();
+        queuer.boot_timings = Some(tx);
+
+        // There is no gateway to connect to, so both boots fail and still emit their timing.
+        queuer.checked_start(ShardId(0), 2).await;
+        queuer.checked_start(ShardId(1), 2).await;
+
+        let first = rx.try_next().unwrap().unwrap();
+        assert_eq!(first.id, ShardId(0));
+        assert!(first.wait < Duration::from_millis(100));
+        assert!(matches!(first.kind, ReconnectType::Reidentify));
+        assert!(first.error.is_some());
+
+        let second = rx.try_next().unwrap().unwrap();
+        assert_eq!(second.id, ShardId(1));
+        assert!(second.wait > Duration::from_secs(WAIT_BETWEEN_BOOTS_IN_SECONDS - 1));
+        assert!(second.wait <= Duration::from_secs(WAIT_BETWEEN_BOOTS_IN_SECONDS));
+        assert!(second.error.is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ratelimit_waits() {
+        let (tx, mut rx) = mpsc::unbounded();
+        let mut queuer = ShardQueuer::offline();
+        queuer.ratelimit_waits = Some(tx);
+
+        queuer.checked_start(ShardId(0), 2).await;
+        queuer.checked_start(ShardId(1), 2).await;
+
+        let wait = rx.try_next().unwrap().unwrap();
+        assert_eq!(wait.id, ShardId(1));
+        assert!(wait.slept > Duration::from_secs(WAIT_BETWEEN_BOOTS_IN_SECONDS - 1));
+        assert!(wait.slept <= Duration::from_secs(WAIT_BETWEEN_BOOTS_IN_SECONDS));
+        assert!(rx.try_next().is_err());
+    }
+
+    #[test]
+    fn jittered_never_shortens() {
+        let duration = Duration::from_secs(WAIT_BETWEEN_BOOTS_IN_SECONDS);
+        for _ in 0..100 {
+            let jittered = jittered(duration, 0.2);
+            assert!(jittered >= duration);
+            assert!(jittered <= duration.mul_f64(1.2));
+        }
+
+        assert_eq!(jittered(duration, -0.2), duration);
+        assert_eq!(jittered(duration, 0.0), duration);
+        assert_eq!(jittered(duration, f64::NAN), duration);
+        assert!(jittered(duration, f64::INFINITY) <= duration * 2);
+        assert!(jittered(Duration::MAX / 4, 1e300) <= Duration::MAX / 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ratelimit_waits_jittered() {
+        let (tx, mut rx) = mpsc::unbounded();
+        let mut queuer = ShardQueuer::offline();
+        queuer.ratelimit_waits = Some(tx);
+        queuer.boot_jitter = Some(0.2);
+
+        for id in 0..5 {
+            queuer.checked_start(ShardId(id), 5).await;
+        }
+
+        let floor = Duration::from_secs(WAIT_BETWEEN_BOOTS_IN_SECONDS - 1);
+        let ceiling = Duration::from_secs(WAIT_BETWEEN_BOOTS_IN_SECONDS).mul_f64(1.2);
+        for id in 1..5 {
+            let wait = rx.try_next().unwrap().unwrap();
+            assert_eq!(wait.id, ShardId(id));
+            assert!(wait.slept > floor);
+            assert!(wait.slept <= ceiling);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_boot_requeues() {
+        let mut queuer = ShardQueuer::offline();
+        queuer.pending.push(ShardId(0));
+
+        queuer.checked_start(ShardId(0), 1).await;
+
+        let in_queue = queuer.queue.iter().map(|shard| (shard.id, shard.total)).collect::<Vec<_>>();
+        assert_eq!(in_queue, vec![(ShardId(0), 1)]);
+        assert_eq!(queuer.pending.ids(), vec![ShardId(0)]);
+        assert!(queuer.runners.lock().await.is_empty());
+        assert!(queuer.last_start.is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fatal_boot_not_requeued() {
+        let mut queuer = ShardQueuer::offline();
+        // The URL can't be parsed, which no retry can fix.
+        *queuer.ws_url.lock().await = String::new();
+        queuer.pending.push(ShardId(0));
+
+        queuer.checked_start(ShardId(0), 1).await;
+
+        assert!(queuer.queue.is_empty());
+        assert!(queuer.pending.is_empty());
+        assert!(queuer.boot_errors.get(ShardId(0)).is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_boot_records_error() {
+        let mut queuer = ShardQueuer::offline();
+        queuer.pending.push(ShardId(0));
+
+        queuer.checked_start(ShardId(0), 1).await;
+
+        let errors = queuer.boot_errors.all();
+        assert_eq!(errors.len(), 1);
+        assert!(!errors[&ShardId(0)].is_empty());
+        assert_eq!(queuer.boot_errors.get(ShardId(0)).as_ref(), errors.get(&ShardId(0)));
+        assert!(queuer.boot_errors.get(ShardId(1)).is_none());
+
+        queuer.boot_errors.remove(ShardId(0));
+        assert!(queuer.boot_errors.all().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn mismatched_total() {
+        let (tx, rx) = mpsc::unbounded();
+        let mut queuer = ShardQueuer::offline();
+        queuer.rx = rx;
+
+        for (id, total) in [(0, 2), (1, 3)] {
+            queuer.pending.push(ShardId(id));
+            tx.unbounded_send(ShardQueuerMessage::Start(ShardId(id), ShardId(total))).unwrap();
+        }
+        tx.unbounded_send(ShardQueuerMessage::Shutdown).unwrap();
+
+        queuer.run().await;
+
+        // The first start fails for lack of a gateway and is re-queued, while the second is
+        // rejected outright.
+        let in_queue = queuer.queue.iter().map(|shard| (shard.id, shard.total)).collect::<Vec<_>>();
+        assert_eq!(in_queue, vec![(ShardId(0), 2)]);
+        assert_eq!(queuer.pending.ids(), vec![ShardId(0)]);
+        assert_eq!(queuer.total, Some(2));
+
+        queuer.add_shards(3).await;
+        assert_eq!(queuer.total, Some(3));
+        assert!(queuer.check_total(ShardId(1), 3));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn duplicate_starts() {
+        let (tx, rx) = mpsc::unbounded();
+        let (timings_tx, mut timings_rx) = mpsc::unbounded();
+        let mut queuer = ShardQueuer::offline();
+        queuer.rx = rx;
+        queuer.boot_timings = Some(timings_tx);
+
+        for _ in 0..2 {
+            queuer.pending.push(ShardId(0));
+            tx.unbounded_send(ShardQueuerMessage::Start(ShardId(0), ShardId(1))).unwrap();
+        }
+        tx.unbounded_send(ShardQueuerMessage::Shutdown).unwrap();
+
+        queuer.run().await;
+
+        // The first start fails for lack of a gateway and is re-queued, which the second start
+        // is coalesced with.
+        let booted = timings_rx.try_next().unwrap().unwrap();
+        assert_eq!(booted.id, ShardId(0));
+        assert!(timings_rx.try_next().is_err());
+
+        let in_queue = queuer.queue.iter().map(|shard| shard.id).collect::<Vec<_>>();
+        assert_eq!(in_queue, vec![ShardId(0)]);
+        assert_eq!(queuer.pending.ids(), vec![ShardId(0)]);
+
+        queuer.pending.push(ShardId(0));
+        queuer.enqueue(ShardInfo::new(ShardId(0), 1));
+        assert_eq!(queuer.queue.len(), 1);
+        assert_eq!(queuer.pending.ids(), vec![ShardId(0)]);
+    }
+
+    #[tokio::test]
+    async fn restart_discards_queued_boot() {
+        let mut queuer = ShardQueuer::offline();
+        for id in 0..2 {
+            queuer.pending.push(ShardId(id));
+            queuer.enqueue(ShardInfo::new(ShardId(id), 2));
+        }
+
+        // The restart's own boot is pending next to the queued one, which it supersedes.
+        queuer.pending.push(ShardId(0));
+        queuer.dequeue(ShardId(0));
+
+        let in_queue = queuer.queue.iter().map(|shard| shard.id).collect::<Vec<_>>();
+        assert_eq!(in_queue, vec![ShardId(1)]);
+        assert_eq!(queuer.pending.ids(), vec![ShardId(1), ShardId(0)]);
+
+        queuer.dequeue(ShardId(0));
+        assert_eq!(queuer.pending.ids(), vec![ShardId(1), ShardId(0)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn start_range() {
+        let (tx, rx) = mpsc::unbounded();
+        let (timings_tx, mut timings_rx) = mpsc::unbounded();
+        let mut queuer = ShardQueuer::offline();
+        queuer.rx = rx;
+        queuer.boot_timings = Some(timings_tx);
+
+        tx.unbounded_send(ShardQueuerMessage::StartRange {
+            start: ShardId(2),
+            end: ShardId(4),
+            total: 10,
+        })
+        .unwrap();
+        tx.unbounded_send(ShardQueuerMessage::Shutdown).unwrap();
+
+        queuer.run().await;
+
+        let mut booted = Vec::new();
+        while let Ok(Some(timing)) = timings_rx.try_next() {
+            booted.push(timing.id);
+        }
+        assert_eq!(booted, vec![ShardId(2), ShardId(3), ShardId(4)]);
+        assert_eq!(queuer.total, Some(10));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn restart_resumes_session() {
+        let (tx, rx) = mpsc::unbounded();
+        let (timings_tx, mut timings_rx) = mpsc::unbounded();
+        let mut queuer = ShardQueuer::offline();
+        queuer.rx = rx;
+        queuer.boot_timings = Some(timings_tx);
+        queuer.last_start = Some(Instant::now());
+
+        let session = ShardSession {
+            session_id: "session".to_string(),
+            seq: 42,
+        };
+        for (id, session) in [(0, Some(session)), (1, None)] {
+            queuer.pending.push(ShardId(id));
+            tx.unbounded_send(ShardQueuerMessage::Restart {
+                id: ShardId(id),
+                total: 2,
+                session,
+            })
+            .unwrap();
+        }
+        tx.unbounded_send(ShardQueuerMessage::Shutdown).unwrap();
+
+        queuer.run().await;
+
+        // The shard with a session attempts to resume without waiting for the IDENTIFY ratelimit,
+        // while the other identifies as usual.
+        let first = timings_rx.try_next().unwrap().unwrap();
+        assert_eq!(first.id, ShardId(0));
+        assert!(matches!(first.kind, ReconnectType::Resume));
+        assert!(first.wait < Duration::from_millis(100));
+
+        let second = timings_rx.try_next().unwrap().unwrap();
+        assert_eq!(second.id, ShardId(1));
+        assert!(matches!(second.kind, ReconnectType::Reidentify));
+        assert!(second.wait > Duration::from_secs(WAIT_BETWEEN_BOOTS_IN_SECONDS - 1));
+
+        // Both boots fail for lack of a gateway, before a RESUME could be sent, so the session is
+        // kept for the re-queued boot.
+        let session = &queuer.sessions[&ShardId(0)];
+        assert_eq!((session.session_id.as_str(), session.seq), ("session", 42));
+        assert!(!queuer.sessions.contains_key(&ShardId(1)));
+        assert_eq!(queuer.pending.ids(), vec![ShardId(0), ShardId(1)]);
+
+        queuer.add_shards(3).await;
+        assert!(queuer.sessions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn intents_for_shard() {
+        let mut queuer = ShardQueuer::offline();
+        let shard_info = ShardInfo::new(ShardId(1), 2);
+        assert_eq!(queuer.intents_for(shard_info), GatewayIntents::non_privileged());
+
+        queuer.intents_fn = Some(Arc::new(|shard_info: ShardInfo| {
+            if shard_info.id == ShardId(1) {
+                GatewayIntents::non_privileged() | GatewayIntents::GUILD_MEMBERS
+            } else {
+                GatewayIntents::non_privileged()
+            }
+        }));
+        assert_eq!(
+            queuer.intents_for(shard_info),
+            GatewayIntents::non_privileged() | GatewayIntents::GUILD_MEMBERS
+        );
+        assert_eq!(
+            queuer.intents_for(ShardInfo::new(ShardId(0), 2)),
+            GatewayIntents::non_privileged()
+        );
+    }
+
+    #[tokio::test]
+    async fn ws_url_for_shard() {
+        let mut queuer = ShardQueuer::offline();
+        *queuer.ws_url.lock().await = "wss://gateway.discord.gg".to_string();
+        let shard_info = ShardInfo::new(ShardId(1), 2);
+        assert!(Arc::ptr_eq(&queuer.ws_url_for(shard_info), &queuer.ws_url));
+
+        queuer.ws_url_fn = Some(Arc::new(|shard_info: ShardInfo| {
+            format!("wss://proxy-{}.example.com", shard_info.id.0 % 2)
+        }));
+        let ws_url = queuer.ws_url_for(shard_info);
+        assert_eq!(*ws_url.lock().await, "wss://proxy-1.example.com");
+        assert_eq!(*queuer.ws_url.lock().await, "wss://gateway.discord.gg");
+    }
+
+    #[tokio::test]
+    async fn queue_limit() {
+        let in_queue =
+            |queuer: &ShardQueuer| queuer.queue.iter().map(|shard| shard.id).collect::<Vec<_>>();
+
+        for (overflow, kept) in [
+            (QueueOverflow::DropOldest, [ShardId(2), ShardId(3)]),
+            (QueueOverflow::DropNewest, [ShardId(0), ShardId(1)]),
+        ] {
+            let mut queuer = ShardQueuer::offline();
+            queuer.queue_limit = Some(QueueLimit {
+                max: 2,
+                overflow,
+            });
+
+            for id in 0..4 {
+                queuer.pending.push(ShardId(id));
+                queuer.enqueue(ShardInfo::new(ShardId(id), 4));
+            }
+
+            assert_eq!(in_queue(&queuer), kept);
+            assert_eq!(queuer.pending.ids(), kept);
+        }
+
+        let mut queuer = ShardQueuer::offline();
+        for id in 0..4 {
+            queuer.enqueue(ShardInfo::new(ShardId(id), 4));
+        }
+        assert_eq!(queuer.queue.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn pending_boots_decrease_on_start() {
+        let mut queuer = ShardQueuer::offline();
+        *queuer.ws_url.lock().await = serve_gateway().await;
+        for id in 0..3 {
+            queuer.pending.push(ShardId(id));
+            queuer.enqueue(ShardInfo::new(ShardId(id), 3));
+        }
+
+        let manager = Arc::clone(&queuer.manager);
+        assert_eq!(manager.lock().await.pending_boots(), 3);
+
+        for remaining in (0..3).rev() {
+            let shard = queuer.queue.pop_front().unwrap();
+            // Skip the wait between IDENTIFYs, which isn't under test.
+            queuer.last_start = None;
+            queuer.checked_start(shard.id, shard.total).await;
+
+            assert_eq!(manager.lock().await.pending_boots(), remaining);
+        }
+        assert!(queuer.boot_errors.get(ShardId(0)).is_none());
+    }
+
+    #[test]
+    fn pending_boots() {
+        let pending = PendingBoots::default();
+        assert!(pending.is_empty());
+
+        for id in 0..3 {
+            pending.push(ShardId(id));
+        }
+        assert_eq!(pending.len(), 3);
+        assert_eq!(pending.ids(), vec![ShardId(0), ShardId(1), ShardId(2)]);
+
+        pending.remove(ShardId(0));
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending.ids(), vec![ShardId(1), ShardId(2)]);
+
+        pending.remove(ShardId(2));
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending.ids(), vec![ShardId(1)]);
+
+        pending.remove(ShardId(1));
+        assert!(pending.is_empty());
+        assert!(pending.ids().is_empty());
+    }
+
+    fn timed_out() -> Error {
+        Error::Io(std::io::Error::from(std::io::ErrorKind::TimedOut))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_transient_errors() {
+        let mut attempts = 0;
+        let result = retry_transient(DEFAULT_BOOT_RETRIES, || {
+            attempts += 1;
+            let result = if attempts <= 2 { Err(timed_out()) } else { Ok(attempts) };
+            async move { result }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 3);
+
+        // Retries are bounded, after which the last error is returned for the shard to be
+        // re-queued.
+        let mut attempts = 0;
+        let result: Result<()> = retry_transient(DEFAULT_BOOT_RETRIES, || {
+            attempts += 1;
+            async { Err(timed_out()) }
+        })
+        .await;
+        assert!(matches!(result, Err(Error::Io(_))));
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn no_retry_on_fatal_errors() {
+        let mut attempts = 0;
+        let result: Result<()> = retry_transient(DEFAULT_BOOT_RETRIES, || {
+            attempts += 1;
+            async { Err(Error::Gateway(GatewayError::InvalidAuthentication)) }
+        })
+        .await;
+        assert!(matches!(result, Err(Error::Gateway(GatewayError::InvalidAuthentication))));
+        assert_eq!(attempts, 1);
+    }
+}
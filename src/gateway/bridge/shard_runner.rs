@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::channel::mpsc::{self, UnboundedReceiver as Receiver, UnboundedSender as Sender};
 use tokio::sync::{Mutex, RwLock};
@@ -13,7 +14,7 @@ use super::event::ShardStageUpdateEvent;
 use super::CollectorCallback;
 #[cfg(feature = "voice")]
 use super::VoiceGatewayManager;
-use super::{ShardManager, ShardRunnerMessage};
+use super::{ShardId, ShardManager, ShardRunnerMessage};
 #[cfg(feature = "cache")]
 use crate::cache::Cache;
 use crate::client::dispatch::dispatch_model;
@@ -39,6 +40,8 @@ pub struct ShardRunner {
     runner_rx: Receiver<ShardRunnerMessage>,
     // channel to send messages to the shard runner from the shard manager
     runner_tx: Sender<ShardRunnerMessage>,
+    // the stage of the shard as last reported to the shard manager
+    stage: ConnectionStage,
     #[cfg(feature = "voice")]
     voice_manager: Option<Arc<dyn VoiceGatewayManager + 'static>>,
     #[cfg(feature = "cache")]
@@ -56,6 +59,7 @@ impl ShardRunner {
         Self {
             runner_rx: rx,
             runner_tx: tx,
+            stage: ConnectionStage::Disconnected,
             data: opt.data,
             event_handlers: opt.event_handlers,
             raw_event_handlers: opt.raw_event_handlers,
@@ -116,8 +120,6 @@ impl ShardRunner {
             let post = shard.stage();
 
             if post != pre {
-                self.update_manager(shard).await;
-
                 for event_handler in self.event_handlers.clone() {
                     let context = self.make_context(shard);
                     let event = ShardStageUpdateEvent {
@@ -180,6 +182,10 @@ impl ShardRunner {
             if !successful && !shard.stage().is_connecting() {
                 return self.request_restart(shard).await;
             }
+
+            // The stage may also change while acting on the event, such as when resuming, so the
+            // manager is only brought up to date once the iteration is done with the shard.
+            self.report_stage(shard.shard_info().id, shard.latency(), shard.stage()).await;
             trace!("[ShardRunner {:?}] loop iteration reached the end.", shard.shard_info());
         }
     }
@@ -390,7 +396,8 @@ impl ShardRunner {
         };
 
         if let Ok(GatewayEvent::HeartbeatAck) = event {
-            self.update_manager(shard).await;
+            self.heartbeat_acknowledged(shard.shard_info().id, shard.latency(), shard.stage())
+                .await;
         }
 
         #[cfg(feature = "voice")]
@@ -425,6 +432,25 @@ impl ShardRunner {
         Ok(())
     }
 
+    /// Records the current stage of the shard, returning whether it differs from the one last
+    /// reported to the manager.
+    fn stage_changed(&mut self, stage: ConnectionStage) -> bool {
+        std::mem::replace(&mut self.stage, stage) != stage
+    }
+
+    /// Brings the manager up to date with the stage of the shard, unless it is the same as the one
+    /// last reported.
+    async fn report_stage(
+        &mut self,
+        id: ShardId,
+        latency: Option<Duration>,
+        stage: ConnectionStage,
+    ) {
+        if self.stage_changed(stage) {
+            self.manager.lock().await.update_shard_latency_and_stage(id, latency, stage).await;
+        }
+    }
+
     #[instrument(skip(self))]
     async fn update_manager(&self, shard: &Shard) {
         self.manager
@@ -433,6 +459,21 @@ impl ShardRunner {
             .update_shard_latency_and_stage(shard.shard_info().id, shard.latency(), shard.stage())
             .await;
     }
+
+    /// Reports the latency measured by a heartbeat acknowledgement to the manager, which also
+    /// broadcasts it to any latency subscribers.
+    async fn heartbeat_acknowledged(
+        &self,
+        id: ShardId,
+        latency: Option<Duration>,
+        stage: ConnectionStage,
+    ) {
+        let manager = self.manager.lock().await;
+        manager.update_shard_latency_and_stage(id, latency, stage).await;
+        if let Some(latency) = latency {
+            manager.broadcast_latency(id, latency);
+        }
+    }
 }
 
 /// Options to be passed to [`ShardRunner::new`].
@@ -449,3 +490,64 @@ pub struct ShardRunnerOptions {
     pub cache: Arc<Cache>,
     pub http: Arc<Http>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::bridge::ShardQueuer;
+
+    fn runner(queuer: &ShardQueuer) -> ShardRunner {
+        ShardRunner::new(ShardRunnerOptions {
+            data: Arc::clone(&queuer.data),
+            event_handlers: vec![],
+            raw_event_handlers: vec![],
+            #[cfg(feature = "framework")]
+            framework: None,
+            manager: Arc::clone(&queuer.manager),
+            #[cfg(feature = "voice")]
+            voice_manager: None,
+            #[cfg(feature = "cache")]
+            cache: Arc::clone(&queuer.cache),
+            http: Arc::clone(&queuer.http),
+        })
+    }
+
+    #[tokio::test]
+    async fn stage_changes() {
+        let queuer = ShardQueuer::offline();
+        let mut runner = runner(&queuer);
+        let _receiver = queuer.insert_runner(ShardId(0), 1, ConnectionStage::Disconnected).await;
+
+        let reported = || async { queuer.runners.lock().await[&ShardId(0)].stage };
+        for stage in [
+            ConnectionStage::Connecting,
+            ConnectionStage::Handshake,
+            ConnectionStage::Identifying,
+            ConnectionStage::Connected,
+        ] {
+            runner.report_stage(ShardId(0), None, stage).await;
+            assert_eq!(reported().await, stage);
+        }
+
+        // Only transitions are reported to the manager, not every iteration at the same stage.
+        queuer.runners.lock().await.get_mut(&ShardId(0)).unwrap().stage =
+            ConnectionStage::Resuming;
+        runner.report_stage(ShardId(0), None, ConnectionStage::Connected).await;
+        assert_eq!(reported().await, ConnectionStage::Resuming);
+    }
+
+    #[tokio::test]
+    async fn heartbeat_ack_broadcasts_latency() {
+        let queuer = ShardQueuer::offline();
+        let runner = runner(&queuer);
+        let mut latencies = queuer.manager.lock().await.subscribe_latency();
+
+        let latency = Duration::from_millis(42);
+        runner.heartbeat_acknowledged(ShardId(3), Some(latency), ConnectionStage::Connected).await;
+        assert_eq!(latencies.try_recv().unwrap(), (ShardId(3), latency));
+
+        // No latency is measured until a heartbeat has been both sent and acknowledged.
+        runner.heartbeat_acknowledged(ShardId(3), None, ConnectionStage::Connected).await;
+        assert!(latencies.try_recv().is_err());
+    }
+}
@@ -453,12 +453,13 @@ impl Shard {
             &Ok(GatewayEvent::Hello(interval)) => {
                 debug!("[{:?}] Received a Hello; interval: {}", self.shard_info, interval);
 
+                // A shard resuming over a fresh connection has no interval to heartbeat at yet.
+                self.heartbeat_interval = Some(std::time::Duration::from_millis(interval));
+
                 if self.stage == ConnectionStage::Resuming {
                     return Ok(None);
                 }
 
-                self.heartbeat_interval = Some(std::time::Duration::from_millis(interval));
-
                 Ok(Some(if self.stage == ConnectionStage::Handshake {
                     ShardAction::Identify
                 } else {
@@ -735,6 +736,21 @@ impl Shard {
         }
     }
 
+    /// Resumes the given session over the shard's current connection, instead of identifying
+    /// once the gateway says Hello.
+    ///
+    /// If Discord invalidates the session, the shard identifies as usual.
+    #[instrument(skip(self, session_id))]
+    pub(crate) async fn resume_session(&mut self, session_id: String, seq: u64) -> Result<()> {
+        debug!("[{:?}] Attempting to resume session at seq {}", self.shard_info, seq);
+
+        self.stage = ConnectionStage::Resuming;
+        self.seq = seq;
+        let session_id = self.session_id.insert(session_id);
+
+        self.client.send_resume(&self.shard_info, session_id, seq, &self.token).await
+    }
+
     #[instrument(skip(self))]
     pub async fn reconnect(&mut self) -> Result<()> {
         info!("[{:?}] Attempting to reconnect", self.shard_info());
@@ -1,5 +1,6 @@
 use std::error::Error as StdError;
 use std::fmt;
+use std::time::Duration;
 
 use reqwest::header::InvalidHeaderValue;
 use reqwest::{Error as ReqwestError, Method, Response, StatusCode};
@@ -38,15 +39,24 @@ pub struct ErrorResponse {
     pub url: String,
     pub method: Method,
     pub error: DiscordJsonError,
+    /// How long to wait for before retrying the request, given when it was ratelimited.
+    pub retry_after: Option<Duration>,
 }
 
 impl ErrorResponse {
     // We need a freestanding from-function since we cannot implement an async From-trait.
     pub async fn from_response(r: Response, method: Method) -> Self {
+        let retry_after = super::ratelimiting::parse_header::<f64>(r.headers(), "retry-after")
+            .ok()
+            .flatten()
+            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+            .map(Duration::from_secs_f64);
+
         ErrorResponse {
             status_code: r.status(),
             url: r.url().to_string(),
             method,
+            retry_after,
             error: decode_resp(r).await.unwrap_or_else(|e| DiscordJsonError {
                 code: -1,
                 message: format!("[Serenity] Could not decode json when receiving error response from discord:, {e}"),
@@ -275,6 +285,7 @@ mod test {
             url: String::from("https://ferris.crab/"),
             method: Method::POST,
             error,
+            retry_after: None,
         };
 
         assert_eq!(error_response, known);
@@ -1,9 +1,13 @@
 use std::borrow::Cow;
 
 use reqwest::multipart::{Form, Part};
+use reqwest::Body;
 
 use crate::builder::CreateAttachment;
 use crate::internal::prelude::*;
+#[cfg(feature = "simd-json")]
+use crate::json::prelude::ValueAccess;
+use crate::json::{from_str, json, to_string, JsonMap, Value};
 
 /// Holder for multipart body. Contains files, multipart fields, and payload_json for creating
 /// requests with attachments.
@@ -21,6 +25,11 @@ impl Multipart {
     pub(crate) fn build_form(self) -> Result<Form> {
         let mut multipart = Form::new();
 
+        let payload_json = match self.payload_json {
+            Some(payload_json) => Some(add_attachment_metadata(payload_json, &self.files)?),
+            None => None,
+        };
+
         for (file_num, file) in self.files.into_iter().enumerate() {
             // For endpoints that require a single file (e.g. create sticker), it will error if the
             // part name is not `file`.
@@ -31,8 +40,15 @@ impl Multipart {
                 Cow::Owned(format!("file{file_num}"))
             };
 
-            let mut part = Part::bytes(file.data);
-            part = guess_mime_str(part, &file.filename)?;
+            let mime = content_type(&file).into_owned();
+            let mut part = match &file.streamed {
+                Some(streamed) => Part::stream_with_length(
+                    Body::wrap_stream(streamed.stream()),
+                    streamed.len,
+                ),
+                None => Part::bytes(file.data),
+            };
+            part = part.mime_str(&mime)?;
             part = part.file_name(file.filename);
             multipart = multipart.part(part_name, part);
         }
@@ -41,7 +57,7 @@ impl Multipart {
             multipart = multipart.text(name, value);
         }
 
-        if let Some(payload_json) = self.payload_json {
+        if let Some(payload_json) = payload_json {
             multipart = multipart.text("payload_json", payload_json);
         }
 
@@ -49,12 +65,112 @@ impl Multipart {
     }
 }
 
-fn guess_mime_str(part: Part, filename: &str) -> Result<Part> {
+/// Inserts the metadata of the uploaded files, such as their descriptions, into the `attachments`
+/// array of the JSON payload. Entries refer to files by their index, and every uploaded file gets
+/// one so that none are left out of the array.
+fn add_attachment_metadata(payload_json: String, files: &[CreateAttachment]) -> Result<String> {
+    if files.iter().all(|file| file.description.is_none()) {
+        return Ok(payload_json);
+    }
+
+    let mut map: JsonMap = from_str(payload_json)?;
+    let attachments = map.entry(String::from("attachments")).or_insert_with(|| json!([]));
+
+    if let Value::Array(attachments) = attachments {
+        for (index, file) in files.iter().enumerate() {
+            let entry = attachments
+                .iter_mut()
+                .find(|a| a.get("id").and_then(|id| id.as_u64()) == Some(index as u64));
+
+            match entry {
+                Some(Value::Object(entry)) => {
+                    if let Some(description) = &file.description {
+                        entry.insert("description".into(), description.clone().into());
+                    }
+                },
+                _ => attachments.push(match &file.description {
+                    Some(description) => json!({
+                        "id": index,
+                        "filename": file.filename,
+                        "description": description,
+                    }),
+                    None => json!({
+                        "id": index,
+                        "filename": file.filename,
+                    }),
+                }),
+            }
+        }
+    }
+
+    to_string(&map)
+}
+
+/// Returns the MIME type of the file, either the one it was given or one guessed from its name.
+fn content_type(file: &CreateAttachment) -> Cow<'_, str> {
+    if let Some(content_type) = &file.content_type {
+        return Cow::Borrowed(content_type);
+    }
+
     // This is required for certain endpoints like create sticker, otherwise the Discord API will
     // respond with a 500 Internal Server Error. The mime type chosen is the same as what reqwest
     // does internally when using Part::file(), but it is not done for any of the other methods we
     // use.
     // https://datatracker.ietf.org/doc/html/rfc7578#section-4.4
-    let mime_type = mime_guess::from_path(filename).first_or_octet_stream();
-    part.mime_str(mime_type.essence_str()).map_err(Into::into)
+    let mime_type = mime_guess::from_path(&file.filename).first_or_octet_stream();
+    Cow::Owned(mime_type.essence_str().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attachment_description() {
+        let files = vec![
+            CreateAttachment::bytes(vec![0], "a.png"),
+            CreateAttachment::bytes(vec![1], "b.png").description("second"),
+        ];
+
+        let payload = add_attachment_metadata(r#"{"content":"hi"}"#.into(), &files).unwrap();
+        let payload: Value = from_str(payload).unwrap();
+        assert_eq!(
+            payload,
+            json!({
+                "content": "hi",
+                "attachments": [
+                    {"id": 0, "filename": "a.png"},
+                    {"id": 1, "filename": "b.png", "description": "second"},
+                ],
+            })
+        );
+
+        let payload = r#"{"attachments":[{"id":1},{"id":"42"},{"id":0}]}"#.into();
+        let payload: Value = from_str(add_attachment_metadata(payload, &files).unwrap()).unwrap();
+        assert_eq!(
+            payload,
+            json!({"attachments": [{"id": 1, "description": "second"}, {"id": "42"}, {"id": 0}]})
+        );
+    }
+
+    #[test]
+    fn attachment_content_type() {
+        let inferred = CreateAttachment::bytes(vec![0], "a.png");
+        assert_eq!(content_type(&inferred), "image/png");
+
+        let unknown = CreateAttachment::bytes(vec![0], "a.unknown");
+        assert_eq!(content_type(&unknown), "application/octet-stream");
+
+        let overridden = CreateAttachment::bytes(vec![0], "a.log").content_type("text/plain");
+        assert_eq!(content_type(&overridden), "text/plain");
+
+        // The part is built with the override as is, so an invalid one fails the request.
+        let files = vec![overridden, CreateAttachment::bytes(vec![0], "b.log").content_type("")];
+        let multipart = Multipart {
+            files,
+            fields: vec![],
+            payload_json: None,
+        };
+        assert!(multipart.build_form().is_err());
+    }
 }
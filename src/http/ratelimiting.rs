@@ -37,6 +37,7 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::future::Future;
 use std::str::{self, FromStr};
 use std::sync::Arc;
 use std::time::SystemTime;
@@ -419,7 +420,7 @@ impl Default for Ratelimit {
     }
 }
 
-fn parse_header<T: FromStr>(headers: &HeaderMap, header: &str) -> Result<Option<T>> {
+pub(super) fn parse_header<T: FromStr>(headers: &HeaderMap, header: &str) -> Result<Option<T>> {
     let Some(header) = headers.get(header) else {return Ok(None)};
 
     let unicode =
@@ -430,16 +431,53 @@ fn parse_header<T: FromStr>(headers: &HeaderMap, header: &str) -> Result<Option<
     Ok(Some(num))
 }
 
+/// Runs `f` until it succeeds, fails with an error other than a ratelimited response, or has been
+/// retried `retries` times, waiting for as long as each ratelimited response asks in between.
+///
+/// `f` is told whether it is making the last attempt, such as to avoid keeping a copy of the
+/// request body around for no reason.
+///
+/// Responses that are ratelimited without telling how long to wait for are not retried.
+pub(crate) async fn retry_on_ratelimit<T, Fut>(
+    retries: u8,
+    mut f: impl FnMut(bool) -> Fut,
+) -> Result<T>
+where
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        let result = f(attempt == retries).await;
+
+        let retry_after = match &result {
+            Err(Error::Http(HttpError::UnsuccessfulRequest(response)))
+                if attempt < retries && response.status_code == StatusCode::TOO_MANY_REQUESTS =>
+            {
+                response.retry_after
+            },
+            _ => None,
+        };
+        let Some(retry_after) = retry_after else { return result };
+
+        attempt += 1;
+        debug!("Retrying ratelimited request in {:?}, attempt {}", retry_after, attempt);
+        sleep(retry_after).await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::error::Error as StdError;
     use std::result::Result as StdResult;
 
     use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+    use reqwest::{Method, StatusCode};
+    use tokio::time::{Duration, Instant};
 
     use super::parse_header;
     use crate::error::Error;
-    use crate::http::HttpError;
+    use crate::http::{DiscordJsonError, ErrorResponse, HttpError};
 
     type Result<T> = StdResult<T, Box<dyn StdError>>;
 
@@ -492,4 +530,61 @@ mod tests {
             Error::Http(HttpError::RateLimitUtf8)
         ));
     }
+
+    fn ratelimited(retry_after: Option<Duration>) -> Error {
+        Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
+            status_code: StatusCode::TOO_MANY_REQUESTS,
+            url: String::from("https://discord.com/api/v10/webhooks/1/token/messages/2"),
+            method: Method::PATCH,
+            error: DiscordJsonError {
+                code: 0,
+                message: String::from("You are being rate limited."),
+                errors: vec![],
+            },
+            retry_after,
+        }))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_on_ratelimit() {
+        let start = Instant::now();
+        let mut attempts = Vec::new();
+        let result = super::retry_on_ratelimit(2, |last| {
+            attempts.push(last);
+            let result = if attempts.len() == 1 {
+                Err(ratelimited(Some(Duration::from_millis(1500))))
+            } else {
+                Ok(attempts.len())
+            };
+            async move { result }
+        })
+        .await;
+
+        // The second attempt succeeds after waiting for as long as the first response asked.
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(attempts, vec![false, false]);
+        assert!(start.elapsed() >= Duration::from_millis(1500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_on_ratelimit_bounded() {
+        let mut attempts = Vec::new();
+        let result: crate::Result<()> = super::retry_on_ratelimit(1, |last| {
+            attempts.push(last);
+            async { Err(ratelimited(Some(Duration::from_secs(1)))) }
+        })
+        .await;
+        assert!(matches!(result, Err(Error::Http(HttpError::UnsuccessfulRequest(_)))));
+        assert_eq!(attempts, vec![false, true]);
+
+        // Without a duration to wait for, the response is returned right away.
+        let mut attempts = 0;
+        let result: crate::Result<()> = super::retry_on_ratelimit(1, |_| {
+            attempts += 1;
+            async { Err(ratelimited(None)) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
 }
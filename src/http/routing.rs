@@ -458,3 +458,29 @@ routes! ('a, {
     api!("/stage-instances/{}", channel_id),
     Some(RatelimitingKind::Path);
 });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guild_integration_path() {
+        let route = Route::GuildIntegration {
+            guild_id: GuildId::new(1),
+            integration_id: IntegrationId::new(2),
+        };
+        assert_eq!(route.path(), "https://discord.com/api/v10/guilds/1/integrations/2");
+
+        // Integrations of the same guild share a ratelimit, while those of other guilds don't.
+        let other = Route::GuildIntegration {
+            guild_id: GuildId::new(1),
+            integration_id: IntegrationId::new(3),
+        };
+        assert_eq!(route.ratelimiting_bucket(), other.ratelimiting_bucket());
+        let other = Route::GuildIntegration {
+            guild_id: GuildId::new(4),
+            integration_id: IntegrationId::new(2),
+        };
+        assert_ne!(route.ratelimiting_bucket(), other.ratelimiting_bucket());
+    }
+}
@@ -200,6 +200,28 @@ impl Colour {
     pub fn hex(self) -> String {
         format!("{:06X}", self.0)
     }
+
+    /// Parses a colour from six hexadecimal digits, optionally preceded by a `#`, such as
+    /// `#644C43`. Returns [`None`] if the string is malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serenity::model::Colour;
+    ///
+    /// assert_eq!(Colour::from_hex("#644C43"), Some(Colour::new(6573123)));
+    /// assert_eq!(Colour::from_hex("644c43"), Some(Colour::new(6573123)));
+    /// assert_eq!(Colour::from_hex("#644C4"), None);
+    /// ```
+    #[must_use]
+    pub fn from_hex(hex: &str) -> Option<Colour> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if digits.len() != 6 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        u32::from_str_radix(digits, 16).ok().map(Colour)
+    }
 }
 
 impl From<i32> for Colour {
@@ -419,6 +441,18 @@ mod test {
         assert_eq!(Colour::from_rgb(0, 0, 255).0, 0x0000FF);
     }
 
+    #[test]
+    fn from_hex() {
+        assert_eq!(Colour::from_hex("#3498DB"), Some(Colour::BLUE));
+        assert_eq!(Colour::from_hex("3498db"), Some(Colour::BLUE));
+        assert_eq!(Colour::from_hex("#000000"), Some(Colour::new(0)));
+
+        let malformed = ["", "#", "#3498D", "#3498DBB", "##3498D", "#3498DG", "+3498DB", "#+498DB"];
+        for malformed in malformed {
+            assert_eq!(Colour::from_hex(malformed), None, "{malformed}");
+        }
+    }
+
     #[test]
     fn r() {
         assert_eq!(Colour::new(0x336123).r(), 0x33);
@@ -3,6 +3,7 @@
 use std::error::Error as StdError;
 use std::fmt;
 
+use super::channel::MessageFlags;
 use super::Permissions;
 
 /// An error returned from the [`model`] module.
@@ -65,6 +66,8 @@ pub enum Error {
     EmbedAmount,
     /// Indicates that the textual content of an embed exceeds the maximum length.
     EmbedTooLarge(usize),
+    /// When attempting to send an embed with over 25 fields.
+    EmbedFieldAmount,
     /// An indication that a [`Guild`] could not be found by [Id][`GuildId`] in the [`Cache`].
     ///
     /// [`Guild`]: super::guild::Guild
@@ -143,9 +146,11 @@ pub enum Error {
     ///
     /// [`ChannelType`]: super::channel::ChannelType
     InvalidChannelType,
-    /// Indicates that the webhook name is under the 2 characters limit.
+    /// Indicates that the webhook name is under the 2 characters limit, or that the username a
+    /// webhook message is sent with is empty.
     NameTooShort,
-    /// Indicates that the webhook name is over the 100 characters limit.
+    /// Indicates that the webhook name is over the 100 characters limit, or that the username a
+    /// webhook message is sent with is over the 80 characters limit.
     NameTooLong,
     /// Indicates that the bot is not author of the message. This error is returned in
     /// private/direct channels.
@@ -158,8 +163,44 @@ pub enum Error {
     NoStickerFileSet,
     /// When attempting to send a message with over 3 stickers.
     StickerAmount,
+    /// When attempting to send a poll with over 10 answers.
+    PollAnswerAmount,
+    /// When attempting to send a poll lasting over 32 days.
+    PollDurationTooLong,
+    /// When attempting to send a message with over 5 action rows.
+    ActionRowAmount,
+    /// When attempting to send an action row with over 5 buttons.
+    ButtonAmount,
     /// When attempting to edit a voice message.
     CannotEditVoiceMessage,
+    /// Indicates that message flags were set which are not allowed in this context.
+    ///
+    /// The disallowed flags are provided.
+    InvalidMessageFlags(MessageFlags),
+    /// Indicates that the webhook's type does not support the action, such as overriding the
+    /// username of an application webhook.
+    InvalidWebhookType,
+    /// Indicates that no attachment of the message has the given filename.
+    AttachmentNotFound(String),
+    /// Indicates that several attachments of the message have the given filename, so it can't be
+    /// told which one is meant.
+    AmbiguousAttachment(String),
+    /// Indicates that the attachments uploaded with a message are larger than allowed.
+    AttachmentTooLarge {
+        /// The total size of the attachments, in bytes.
+        total: u64,
+        /// The maximum total size, in bytes.
+        limit: u64,
+    },
+    /// Indicates that a string is not a valid hexadecimal colour.
+    ///
+    /// The string is provided.
+    InvalidColour(String),
+    /// Indicates that an embed refers to an attachment by a filename containing a path separator,
+    /// which no uploaded attachment can have.
+    ///
+    /// The filename is provided.
+    InvalidAttachmentFilename(String),
 }
 
 impl Error {
@@ -184,6 +225,7 @@ impl fmt::Display for Error {
             Self::DeleteMessageDaysAmount(_) => f.write_str("Invalid delete message days."),
             Self::EmbedAmount => f.write_str("Too many embeds in a message."),
             Self::EmbedTooLarge(_) => f.write_str("Embed too large."),
+            Self::EmbedFieldAmount => f.write_str("Too many fields in an embed."),
             Self::GuildNotFound => f.write_str("Guild not found in the cache."),
             Self::RoleNotFound => f.write_str("Role not found in the cache."),
             Self::MemberNotFound => f.write_str("Member not found in the cache."),
@@ -207,7 +249,24 @@ impl fmt::Display for Error {
             Self::DeleteNitroSticker => f.write_str("Cannot delete an official sticker."),
             Self::NoStickerFileSet => f.write_str("Sticker file is not set."),
             Self::StickerAmount => f.write_str("Too many stickers in a message."),
+            Self::PollAnswerAmount => f.write_str("Too many answers in a poll."),
+            Self::PollDurationTooLong => f.write_str("Poll duration is over the limit."),
+            Self::ActionRowAmount => f.write_str("Too many action rows in a message."),
+            Self::ButtonAmount => f.write_str("Too many buttons in an action row."),
             Self::CannotEditVoiceMessage => f.write_str("Cannot edit voice message."),
+            Self::InvalidMessageFlags(_) => f.write_str("Invalid message flags."),
+            Self::InvalidWebhookType => f.write_str("The webhook type cannot perform the action."),
+            Self::AttachmentNotFound(_) => f.write_str("Attachment not found in the message."),
+            Self::AmbiguousAttachment(_) => {
+                f.write_str("Several attachments in the message have the same filename.")
+            },
+            Self::AttachmentTooLarge {
+                ..
+            } => f.write_str("Attachments too large."),
+            Self::InvalidColour(_) => f.write_str("Invalid hexadecimal colour."),
+            Self::InvalidAttachmentFilename(_) => {
+                f.write_str("Attachment filename contains a path separator.")
+            },
         }
     }
 }
@@ -947,7 +947,7 @@ impl GuildId {
         http.as_ref().get_guild_sticker(self, sticker_id).await
     }
 
-    /// Gets all integration of the guild.
+    /// Gets all integration of the guild, with [`Integration::guild_id`] set on each of them.
     ///
     /// Requires the [Manage Guild] permission.
     ///
@@ -959,7 +959,14 @@ impl GuildId {
     /// [Manage Guild]: Permissions::MANAGE_GUILD
     #[inline]
     pub async fn integrations(self, http: impl AsRef<Http>) -> Result<Vec<Integration>> {
-        http.as_ref().get_guild_integrations(self).await
+        let mut integrations = http.as_ref().get_guild_integrations(self).await?;
+
+        // Discord omits the guild Id on this endpoint.
+        for integration in &mut integrations {
+            integration.guild_id = Some(self);
+        }
+
+        Ok(integrations)
     }
 
     /// Gets all of the guild's invites.
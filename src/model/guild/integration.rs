@@ -1,8 +1,13 @@
+use std::hash::{Hash, Hasher};
+
 use super::*;
 use crate::model::Timestamp;
 
 /// Various information about integrations.
 ///
+/// Integrations are compared and hashed by their [`Self::id`] alone, so that two snapshots of the
+/// same integration are equal even if their other fields differ.
+///
 /// [Discord docs](https://discord.com/developers/docs/resources/guild#integration-object),
 /// [extra fields 1](https://discord.com/developers/docs/topics/gateway-events#integration-create),
 /// [extra fields 2](https://discord.com/developers/docs/topics/gateway-events#integration-update),
@@ -27,7 +32,8 @@ pub struct Integration {
     pub revoked: Option<bool>,
     pub application: Option<IntegrationApplication>,
     pub scopes: Option<Vec<Scope>>,
-    /// Only present in [`IntegrationCreateEvent`] and [`IntegrationUpdateEvent`].
+    /// Present in [`IntegrationCreateEvent`] and [`IntegrationUpdateEvent`], and filled in by
+    /// [`GuildId::integrations`].
     pub guild_id: Option<GuildId>,
 }
 
@@ -45,6 +51,114 @@ enum_number! {
     }
 }
 
+#[cfg(feature = "model")]
+impl Integration {
+    /// Deletes the integration from its guild, removing any associated webhooks and kicking the
+    /// associated bot if there is one.
+    ///
+    /// Refer to [`GuildId::delete_integration`] for more information.
+    ///
+    /// **Note**: Requires the [Manage Guild] permission.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ModelError::GuildNotFound`] if [`Self::guild_id`] is not set.
+    ///
+    /// Returns [`Error::Http`] if the current user lacks permission.
+    ///
+    /// [Manage Guild]: Permissions::MANAGE_GUILD
+    pub async fn delete(&self, http: impl AsRef<Http>) -> Result<()> {
+        let guild_id = self.guild_id.ok_or(Error::Model(ModelError::GuildNotFound))?;
+        guild_id.delete_integration(http, self.id).await
+    }
+
+    /// Starts a sync of the integration.
+    ///
+    /// Refer to [`GuildId::start_integration_sync`] for more information.
+    ///
+    /// **Note**: Requires the [Manage Guild] permission.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ModelError::GuildNotFound`] if [`Self::guild_id`] is not set.
+    ///
+    /// Returns [`Error::Http`] if the current user lacks permission.
+    ///
+    /// [Manage Guild]: Permissions::MANAGE_GUILD
+    pub async fn sync(&self, http: impl AsRef<Http>) -> Result<()> {
+        let guild_id = self.guild_id.ok_or(Error::Model(ModelError::GuildNotFound))?;
+        guild_id.start_integration_sync(http, self.id).await
+    }
+}
+
+impl Integration {
+    /// Returns whether the integration is enabled and has not been revoked.
+    #[inline]
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.revoked != Some(true)
+    }
+
+    /// Filters the given integrations down to those that are [active].
+    ///
+    /// [active]: Self::is_active
+    pub fn active(integrations: &[Integration]) -> impl Iterator<Item = &Integration> {
+        integrations.iter().filter(|integration| integration.is_active())
+    }
+
+    /// Finds the integration with the given Id among the given integrations, such as to resolve
+    /// the [`AuditLogEntry::target_id`] of an integration-related audit log entry.
+    ///
+    /// [`AuditLogEntry::target_id`]: crate::model::guild::audit_log::AuditLogEntry::target_id
+    #[must_use]
+    pub fn find(integrations: &[Integration], id: IntegrationId) -> Option<&Integration> {
+        integrations.iter().find(|integration| integration.id == id)
+    }
+
+    /// Returns the name of the integration's account along with its Id, such as
+    /// `account name (1234567)`, for instance to render in logs.
+    #[must_use]
+    pub fn account_display(&self) -> String {
+        format!("{} ({})", self.account.name, self.account.id)
+    }
+
+    /// Returns whether the integration has been granted the given OAuth2 scope.
+    ///
+    /// An integration without [`Self::scopes`] is treated as having no scopes granted.
+    #[must_use]
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.as_ref().is_some_and(|scopes| scopes.contains(&scope))
+    }
+
+    /// Returns the scopes out of `needed` that the integration has not been granted, which the
+    /// application has to be re-authorized with.
+    #[must_use]
+    pub fn requires_reauth(&self, needed: &[Scope]) -> Vec<Scope> {
+        needed.iter().copied().filter(|scope| !self.has_scope(*scope)).collect()
+    }
+}
+
+impl IntegrationExpireBehaviour {
+    /// Returns a human-readable description of the behaviour.
+    #[inline]
+    #[must_use]
+    pub const fn description(&self) -> &'static str {
+        match *self {
+            Self::RemoveRole => "Remove role",
+            Self::Kick => "Kick",
+            Self::Unknown(_) => "Unknown expire behavior",
+        }
+    }
+
+    /// Returns whether the behaviour is one known to this library, as opposed to
+    /// [`Self::Unknown`].
+    #[inline]
+    #[must_use]
+    pub const fn is_known(&self) -> bool {
+        !matches!(self, Self::Unknown(_))
+    }
+}
+
 impl From<Integration> for IntegrationId {
     /// Gets the Id of integration.
     fn from(integration: Integration) -> IntegrationId {
@@ -52,6 +166,20 @@ impl From<Integration> for IntegrationId {
     }
 }
 
+impl PartialEq for Integration {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Integration {}
+
+impl Hash for Integration {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.id.hash(hasher);
+    }
+}
+
 /// Integration account object.
 ///
 /// [Discord docs](https://discord.com/developers/docs/resources/guild#integration-account-object).
@@ -74,3 +202,216 @@ pub struct IntegrationApplication {
     pub description: String,
     pub bot: Option<User>,
 }
+
+impl IntegrationApplication {
+    /// Returns whether the application has a bot user.
+    #[inline]
+    #[must_use]
+    pub fn is_bot(&self) -> bool {
+        self.bot.is_some()
+    }
+
+    /// Returns the tag of the application's bot user, if it has one.
+    ///
+    /// Refer to [`User::tag`] for more information.
+    #[cfg(feature = "model")]
+    #[must_use]
+    pub fn bot_tag(&self) -> Option<String> {
+        self.bot.as_ref().map(User::tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::json::{from_value, json};
+
+    fn integration(guild_id: Option<u64>) -> Integration {
+        from_value(json!({
+            "id": "33590653072239123",
+            "name": "A Name",
+            "type": "twitch",
+            "enabled": true,
+            "account": {"id": "1234567", "name": "account name"},
+            "guild_id": guild_id,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn guild_id_deserialized() {
+        assert_eq!(integration(None).guild_id, None);
+        assert_eq!(
+            integration(Some(41771983423143937)).guild_id,
+            Some(GuildId::new(41771983423143937))
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn guild_id_filled_in() {
+        use std::io::{Read, Write};
+
+        use crate::http::HttpBuilder;
+
+        // The list endpoint omits the field entirely, rather than sending it as null.
+        let body = json!([{
+            "id": "33590653072239123",
+            "name": "A Name",
+            "type": "twitch",
+            "enabled": true,
+            "account": {"id": "1234567", "name": "account name"},
+        }])
+        .to_string();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            assert!(socket.read(&mut request).unwrap() > 0);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).unwrap();
+        });
+
+        let http = HttpBuilder::new("token")
+            .proxy(format!("http://{addr}"))
+            .ratelimiter_disabled(true)
+            .build();
+        let integrations = GuildId::new(41771983423143937).integrations(&http).await.unwrap();
+        assert_eq!(integrations.len(), 1);
+        assert_eq!(integrations[0].guild_id, Some(GuildId::new(41771983423143937)));
+    }
+
+    #[test]
+    fn active_integrations() {
+        let mut revoked = integration(None);
+        revoked.revoked = Some(true);
+        let mut disabled = integration(None);
+        disabled.enabled = false;
+        let mut kept = integration(None);
+        kept.revoked = Some(false);
+        let unknown = integration(None);
+
+        assert!(!revoked.is_active());
+        assert!(!disabled.is_active());
+        assert!(kept.is_active());
+        assert!(unknown.is_active());
+
+        let integrations = [revoked, disabled, kept, unknown];
+        let active: Vec<_> = Integration::active(&integrations).collect();
+        assert_eq!(active.len(), 2);
+        assert_eq!(active[0].revoked, Some(false));
+        assert_eq!(active[1].revoked, None);
+    }
+
+    #[test]
+    fn find_by_id() {
+        let mut other = integration(None);
+        other.id = IntegrationId::new(1);
+        let integrations = [other, integration(None)];
+
+        let found = Integration::find(&integrations, IntegrationId::new(33590653072239123));
+        assert_eq!(found.map(|integration| integration.name.as_str()), Some("A Name"));
+        assert!(std::ptr::eq(found.unwrap(), &integrations[1]));
+        assert!(Integration::find(&integrations, IntegrationId::new(2)).is_none());
+        assert!(Integration::find(&[], IntegrationId::new(1)).is_none());
+    }
+
+    #[test]
+    fn account_display() {
+        assert_eq!(integration(None).account_display(), "account name (1234567)");
+    }
+
+    #[test]
+    fn scopes() {
+        let mut integration = integration(None);
+        assert!(!integration.has_scope(Scope::Bot));
+        assert_eq!(integration.requires_reauth(&[Scope::Bot, Scope::ApplicationsCommands]), vec![
+            Scope::Bot,
+            Scope::ApplicationsCommands
+        ]);
+
+        integration.scopes = Some(vec![Scope::Bot]);
+        assert!(integration.has_scope(Scope::Bot));
+        assert!(!integration.has_scope(Scope::ApplicationsCommands));
+        assert_eq!(integration.requires_reauth(&[Scope::Bot, Scope::ApplicationsCommands]), vec![
+            Scope::ApplicationsCommands
+        ]);
+        assert!(integration.requires_reauth(&[Scope::Bot]).is_empty());
+    }
+
+    #[test]
+    fn equal_by_id() {
+        let enabled = integration(None);
+        let mut disabled = integration(None);
+        disabled.enabled = false;
+        assert_eq!(enabled, disabled);
+
+        let mut other = integration(None);
+        other.id = IntegrationId::new(1);
+        assert_ne!(enabled, other);
+
+        let set = HashSet::from([enabled, disabled, other]);
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn expire_behaviour_description() {
+        let behaviour = IntegrationExpireBehaviour::RemoveRole;
+        assert_eq!(behaviour.description(), "Remove role");
+        assert!(behaviour.is_known());
+
+        let behaviour = IntegrationExpireBehaviour::Kick;
+        assert_eq!(behaviour.description(), "Kick");
+        assert!(behaviour.is_known());
+
+        let behaviour = IntegrationExpireBehaviour::Unknown(42);
+        assert_eq!(behaviour.description(), "Unknown expire behavior");
+        assert!(!behaviour.is_known());
+    }
+
+    #[test]
+    fn application_bot() {
+        let application: IntegrationApplication = from_value(json!({
+            "id": "33590653072239123",
+            "name": "An Application",
+            "icon": null,
+            "description": "",
+        }))
+        .unwrap();
+        assert!(!application.is_bot());
+        #[cfg(feature = "model")]
+        assert_eq!(application.bot_tag(), None);
+
+        let application: IntegrationApplication = from_value(json!({
+            "id": "33590653072239123",
+            "name": "An Application",
+            "icon": null,
+            "description": "",
+            "bot": {"id": "33590653072239124", "username": "bot", "discriminator": "1234"},
+        }))
+        .unwrap();
+        assert!(application.is_bot());
+        #[cfg(feature = "model")]
+        assert_eq!(application.bot_tag().as_deref(), Some("bot#1234"));
+    }
+
+    #[cfg(feature = "model")]
+    #[tokio::test]
+    async fn actions_require_guild_id() {
+        let http = Http::new("token");
+        let integration = integration(None);
+
+        let err = integration.delete(&http).await.unwrap_err();
+        assert!(matches!(err, Error::Model(ModelError::GuildNotFound)));
+
+        let err = integration.sync(&http).await.unwrap_err();
+        assert!(matches!(err, Error::Model(ModelError::GuildNotFound)));
+    }
+}
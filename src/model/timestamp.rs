@@ -31,6 +31,7 @@
 
 use std::fmt;
 use std::str::FromStr;
+use std::time::SystemTime;
 
 #[cfg(feature = "chrono")]
 pub use chrono::ParseError as InnerError;
@@ -93,7 +94,7 @@ impl Timestamp {
     ///
     /// Returns `Err` if the value is invalid.
     pub fn from_unix_timestamp(secs: i64) -> Result<Self, InvalidTimestamp> {
-        Self::from_millis(secs * 1000)
+        secs.checked_mul(1000).ok_or(InvalidTimestamp).and_then(Self::from_millis)
     }
 
     /// Returns the number of non-leap seconds since January 1, 1970 0:00:00 UTC
@@ -222,6 +223,28 @@ impl<'a> std::convert::TryFrom<&'a str> for Timestamp {
     }
 }
 
+impl TryFrom<i64> for Timestamp {
+    type Error = InvalidTimestamp;
+
+    /// Creates a timestamp from a UNIX timestamp (seconds since 1970).
+    fn try_from(secs: i64) -> Result<Self, Self::Error> {
+        Timestamp::from_unix_timestamp(secs)
+    }
+}
+
+impl TryFrom<SystemTime> for Timestamp {
+    type Error = InvalidTimestamp;
+
+    /// Creates a timestamp from a [`SystemTime`], truncated to millisecond precision.
+    fn try_from(time: SystemTime) -> Result<Self, Self::Error> {
+        let millis = match time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(after) => i64::try_from(after.as_millis()),
+            Err(before) => i64::try_from(before.duration().as_millis()).map(|millis| -millis),
+        };
+        millis.map_err(|_| InvalidTimestamp).and_then(Timestamp::from_millis)
+    }
+}
+
 impl From<&Timestamp> for Timestamp {
     fn from(ts: &Timestamp) -> Self {
         *ts
@@ -230,8 +253,14 @@ impl From<&Timestamp> for Timestamp {
 
 #[cfg(test)]
 mod tests {
+    use std::time::{Duration, SystemTime};
+
     use super::Timestamp;
 
+    fn rfc3339(timestamp: Timestamp) -> String {
+        crate::json::to_value(timestamp).unwrap().as_str().unwrap().to_string()
+    }
+
     #[test]
     fn from_unix_timestamp() {
         let timestamp = Timestamp::from_unix_timestamp(1462015105).unwrap();
@@ -242,4 +271,32 @@ mod tests {
             assert_eq!(timestamp.to_string(), "2016-04-30T11:18:25Z");
         }
     }
+
+    #[test]
+    fn from_other_types() {
+        let expected = Timestamp::parse("2016-04-30T11:18:25Z").unwrap();
+
+        let timestamp = Timestamp::try_from(1462015105_i64).unwrap();
+        assert_eq!(timestamp, expected);
+        assert_eq!(rfc3339(timestamp), "2016-04-30T11:18:25Z");
+
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1462015105);
+        let timestamp = Timestamp::try_from(time).unwrap();
+        assert_eq!(timestamp, expected);
+        assert_eq!(rfc3339(timestamp), "2016-04-30T11:18:25Z");
+
+        let timestamp = Timestamp::try_from("2016-04-30T11:18:25+00:00").unwrap();
+        assert_eq!(timestamp, expected);
+        assert_eq!(rfc3339(timestamp), "2016-04-30T11:18:25Z");
+
+        let time = SystemTime::UNIX_EPOCH - Duration::from_secs(1);
+        assert_eq!(Timestamp::try_from(time).unwrap().unix_timestamp(), -1);
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert!(Timestamp::try_from(i64::MAX).is_err());
+        assert!(Timestamp::try_from(i64::MIN).is_err());
+        assert!(Timestamp::try_from(1_000_000_000_000_000_i64).is_err());
+    }
 }
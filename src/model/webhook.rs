@@ -308,7 +308,8 @@ impl Webhook {
     ///
     /// # Errors
     ///
-    /// Returns an [`Error::Model`] if [`Self::token`] is [`None`].
+    /// Returns an [`Error::Model`] if [`Self::token`] is [`None`], or if the username or avatar
+    /// is overridden for a webhook of kind [`WebhookType::Application`], which ignores them.
     ///
     /// May also return an [`Error::Http`] if the content is malformed, or if the webhook's token
     /// is invalid.
@@ -322,6 +323,9 @@ impl Webhook {
         builder: ExecuteWebhook,
     ) -> Result<Option<Message>> {
         let token = self.token.as_ref().ok_or(ModelError::NoTokenSet)?.expose_secret();
+        if self.kind == WebhookType::Application && builder.overrides_identity() {
+            return Err(Error::Model(ModelError::InvalidWebhookType));
+        }
         builder.execute(cache_http, (self.id, token, wait)).await
     }
 